@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_1::{count_depth_increase, count_sum_depth_increase};
+use input::testing::xorshift;
+
+/// Input sizes relative to the bundled `readings.txt`.
+const SCALES: [usize; 3] = [1, 100, 10_000];
+
+/// Depths wandering mostly downwards like the bundled readings, never reaching 0.
+fn generate_readings(lines: usize) -> String {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut depth: i64 = 150;
+    let mut readings = String::with_capacity(lines * 6);
+    for _ in 0..lines {
+        depth = (depth + (xorshift(&mut state) % 21) as i64 - 8).max(1);
+        readings.push_str(&depth.to_string());
+        readings.push('\n');
+    }
+    readings
+}
+
+fn solvers_benchmark(c: &mut Criterion) {
+    let bundled = include_str!("../readings.txt").lines().count();
+    let mut group = c.benchmark_group("day_1");
+    group.sample_size(10);
+    for scale in SCALES {
+        let lines = bundled * scale;
+        let readings = generate_readings(lines);
+        let size = format!("{}x", scale);
+        group.throughput(Throughput::Elements(lines as u64));
+        group.bench_with_input(
+            BenchmarkId::new("count_depth_increase", &size),
+            &readings,
+            |b, readings| b.iter(|| count_depth_increase(&mut readings.as_bytes())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("count_sum_depth_increase", &size),
+            &readings,
+            |b, readings| b.iter(|| count_sum_depth_increase(&mut readings.as_bytes())),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solvers_benchmark);
+criterion_main!(benches);
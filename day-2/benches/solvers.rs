@@ -0,0 +1,60 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_2::{drive, Position, PositionAim};
+use input::testing::xorshift;
+
+/// Input sizes relative to the bundled `src/input.txt`.
+const SCALES: [usize; 3] = [1, 100, 10_000];
+
+/// Commands like the bundled ones. The aim stays below 20 so neither submarine ever
+/// goes above the surface or past `u32::MAX` in depth.
+fn generate_commands(lines: usize) -> String {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut aim = 0;
+    let mut commands = String::with_capacity(lines * 10);
+    for _ in 0..lines {
+        let strength = xorshift(&mut state) % 9 + 1;
+        let direction = match xorshift(&mut state) % 3 {
+            0 => "forward",
+            1 if aim + strength < 20 => "down",
+            _ if strength <= aim => "up",
+            _ => "forward",
+        };
+        match direction {
+            "down" => aim += strength,
+            "up" => aim -= strength,
+            _ => {}
+        }
+        commands.push_str(&format!("{} {}\n", direction, strength));
+    }
+    commands
+}
+
+fn solvers_benchmark(c: &mut Criterion) {
+    let bundled = include_str!("../src/input.txt").lines().count();
+    let mut group = c.benchmark_group("day_2");
+    group.sample_size(10);
+    for scale in SCALES {
+        let lines = bundled * scale;
+        let commands = generate_commands(lines);
+        let size = format!("{}x", scale);
+        group.throughput(Throughput::Elements(lines as u64));
+        group.bench_with_input(BenchmarkId::new("drive_position", &size), &commands, |b, commands| {
+            b.iter(|| {
+                let mut submarine = Position { x_pos: 0, y_pos: 0 };
+                drive(&mut commands.as_bytes(), &mut submarine);
+                submarine
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("drive_position_aim", &size), &commands, |b, commands| {
+            b.iter(|| {
+                let mut submarine = PositionAim { x_pos: 0, y_pos: 0, aim: 0 };
+                drive(&mut commands.as_bytes(), &mut submarine);
+                submarine
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solvers_benchmark);
+criterion_main!(benches);
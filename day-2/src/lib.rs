@@ -0,0 +1,208 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+pub struct Position {
+    pub x_pos: u32,
+    pub y_pos: u32,
+}
+
+pub struct PositionAim {
+    pub x_pos: u32,
+    pub y_pos: u32,
+    pub aim: u32,
+}
+
+pub trait Submarine {
+    fn change_position(&mut self, direction: &str, strength: u32);
+    fn calculate_position(&self) -> u32;
+}
+
+impl Submarine for Position {
+    fn change_position(&mut self, direction: &str, strength: u32) {
+        match direction {
+            "forward" => self.x_pos += strength,
+            "down" => self.y_pos += strength,
+            "up" => self.y_pos -= strength,
+            _ => println!("Unknown command, skipping!")
+        }
+    }
+
+    fn calculate_position(&self) -> u32 {
+        self.x_pos * self.y_pos
+    }
+}
+
+impl Submarine for PositionAim {
+    fn change_position(&mut self, direction: &str, strength: u32) {
+        match direction {
+            "forward" => {
+                self.x_pos += strength;
+                self.y_pos += self.aim * strength;
+            },
+            "down" => self.aim += strength,
+            "up" => self.aim -= strength,
+            _ => println!("Unknown command, skipping!")
+        }
+    }
+
+    fn calculate_position(&self) -> u32 {
+        self.x_pos *self.y_pos
+    }
+}
+
+/// One line of the course, such as `forward 5`.
+#[derive(Debug, PartialEq)]
+pub struct Command {
+    pub direction: String,
+    pub strength: u32,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(command: &str) -> Result<Self, Self::Err> {
+        let (direction, strength) = command
+            .split_once(' ')
+            .ok_or_else(|| format!("Invalid command format {}!", command))?;
+        let strength = strength
+            .parse()
+            .map_err(|_| format!("Cannot parse value {}!", strength))?;
+        Ok(Command { direction: direction.to_string(), strength })
+    }
+}
+
+fn execute_command<S: Submarine>(command: &Command, submarine: &mut S) {
+    submarine.change_position(&command.direction, command.strength);
+}
+
+/// Follows every command, skipping blank lines and lines that aren't commands.
+pub fn drive<R: BufRead, S: Submarine>(commands: &mut R, submarine: &mut S) {
+    for command in input::parse_lines::<Command, _>(commands) {
+        match command {
+            Ok(command) => execute_command(&command, submarine),
+            Err(error) => println!("Skipping invalid command at {}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_return_command() {
+        let actual: Command = "up 10".parse().unwrap();
+        let expected = Command { direction: "up".to_string(), strength: 10 };
+
+        assert_eq!(actual, expected, "command is not up 10");
+    }
+
+    #[test]
+    fn test_parse_command_return_error() {
+        let actual = "down10".parse::<Command>();
+        assert_eq!(actual, Err("Invalid command format down10!".to_string()));
+
+        let actual = "down ten".parse::<Command>();
+        assert_eq!(actual, Err("Cannot parse value ten!".to_string()));
+    }
+
+    #[test]
+    fn test_parse_lines_keeps_command_error() {
+        let actual = input::parse_lines::<Command, _>("down10\n".as_bytes()).next().unwrap();
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "line 1: cannot parse \"down10\": Invalid command format down10!"
+        );
+    }
+
+    #[test]
+    fn test_execute_forward_command_for_position() {
+        let command: Command = "forward 5".parse().unwrap();
+        let mut actual: Position = Position{ x_pos: 0, y_pos: 0};
+        let expected: Position = Position{ x_pos: 5, y_pos: 0};
+
+        execute_command(&command, &mut actual);
+        assert_eq!(actual.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(actual.y_pos, expected.y_pos, "y_pos are not equal");
+    }
+
+    #[test]
+    fn test_execute_up_command_for_position() {
+        let command: Command = "up 2".parse().unwrap();
+        let mut actual: Position = Position{ x_pos: 2, y_pos: 12 };
+        let expected: Position = Position{ x_pos: 2, y_pos: 10 };
+
+        execute_command(&command, &mut actual);
+        assert_eq!(actual.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(actual.y_pos, expected.y_pos, "y_pos are not equal");
+    }
+
+    #[test]
+    fn test_execute_down_command_for_position() {
+        let command: Command = "down 3".parse().unwrap();
+        let mut actual: Position = Position{ x_pos: 2, y_pos: 12 };
+        let expected: Position = Position{ x_pos: 2, y_pos: 15};
+
+        execute_command(&command, &mut actual);
+        assert_eq!(actual.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(actual.y_pos, expected.y_pos, "y_pos are not equal");
+    }
+
+    #[test]
+    fn test_execute_forward_command_for_positionaim() {
+        let command: Command = "forward 5".parse().unwrap();
+        let mut actual: PositionAim = PositionAim{ x_pos: 0, y_pos: 10, aim: 2 };
+        let expected: PositionAim = PositionAim{ x_pos: 5, y_pos: 20, aim: 2 };
+
+        execute_command(&command, &mut actual);
+        assert_eq!(actual.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(actual.y_pos, expected.y_pos, "y_pos are not equal");
+        assert_eq!(actual.aim, expected.aim, "aim are not equal");
+    }
+
+    #[test]
+    fn test_execute_up_command_for_positionaim() {
+        let command: Command = "up 2".parse().unwrap();
+        let mut actual: PositionAim = PositionAim{ x_pos: 2, y_pos: 12, aim: 3 };
+        let expected: PositionAim = PositionAim{ x_pos: 2, y_pos: 12, aim: 1 };
+
+        execute_command(&command, &mut actual);
+        assert_eq!(actual.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(actual.y_pos, expected.y_pos, "y_pos are not equal");
+        assert_eq!(actual.aim, expected.aim, "aim are not equal");
+    }
+
+    #[test]
+    fn test_execute_down_command_for_positionaim() {
+        let command: Command = "down 3".parse().unwrap();
+        let mut actual: PositionAim = PositionAim{ x_pos: 2, y_pos: 12, aim: 8 };
+        let expected: PositionAim = PositionAim{ x_pos: 2, y_pos: 12, aim: 11 };
+
+        execute_command(&command, &mut actual);
+        assert_eq!(actual.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(actual.y_pos, expected.y_pos, "y_pos are not equal");
+        assert_eq!(actual.aim, expected.aim, "aim are not equal");
+    }
+
+    #[test]
+    fn test_drive_skips_blank_and_invalid_lines() {
+        let commands = "forward 5\n\ndown10\ndown 5\n";
+        let expected: Position = Position{ x_pos: 5, y_pos: 5 };
+
+        let mut position: Position = Position{ x_pos: 0, y_pos: 0 };
+        drive(&mut commands.as_bytes(), &mut position);
+        assert_eq!(position.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(position.y_pos, expected.y_pos, "y_pos are not equal");
+    }
+
+    #[test]
+    fn test_drive() {
+        let commands = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        let expected: Position = Position{ x_pos: 15, y_pos: 10 };
+
+        let mut position: Position = Position{ x_pos: 0, y_pos: 0 };
+        drive(&mut commands.as_bytes(), &mut position);
+        assert_eq!(position.x_pos, expected.x_pos, "x_pos are not equal");
+        assert_eq!(position.y_pos, expected.y_pos, "y_pos are not equal");
+    }
+}
@@ -0,0 +1,21 @@
+//! Helpers shared by the benches.
+
+use input::testing::xorshift;
+
+/// Bits in every generated reading, the same as in `src/input.txt`.
+pub const WIDTH: usize = 12;
+
+/// `lines` pseudo-random readings of `WIDTH` bits. The same seed is used every time, so
+/// every run benches the same input.
+pub fn generate_input(lines: usize) -> String {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut input = String::with_capacity(lines * (WIDTH + 1));
+    for _ in 0..lines {
+        input.push_str(&format!(
+            "{:0width$b}\n",
+            xorshift(&mut state) & ((1 << WIDTH) - 1),
+            width = WIDTH
+        ));
+    }
+    input
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::generate_input;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use day_3::{run, LifeSupportReport};
+
+const LINES: usize = 1_000_000;
+
+/// The filter `LifeSupportReport` used before readings were bit-packed: every pass clones
+/// the surviving `String`s and looks bits up with `chars().nth(i)`. The "strings" bench
+/// times it against the bit-packed report on the same million readings.
+fn string_filter(readings: &[String], keep_most_common: bool) -> u32 {
+    let mut readings: Vec<String> = readings.to_vec();
+    for i in 0..readings[0].len() {
+        let mut bags = [vec![], vec![]];
+        for reading in &readings {
+            match reading.chars().nth(i).unwrap() {
+                '1' => bags[1].push(reading.clone()),
+                '0' => bags[0].push(reading.clone()),
+                _ => continue,
+            }
+        }
+        let keep_ones = (bags[0].len() <= bags[1].len()) == keep_most_common;
+        readings = bags[keep_ones as usize].clone();
+        if readings.len() == 1 {
+            break;
+        }
+    }
+    u32::from_str_radix(&readings[0], 2).unwrap()
+}
+
+fn life_support_benchmark(c: &mut Criterion) {
+    let input = generate_input(LINES);
+    let strings: Vec<String> = input.lines().map(|line| line.to_string()).collect();
+    let mut report = LifeSupportReport::default();
+    run(&mut input.as_bytes(), &mut report).unwrap();
+    assert_eq!(
+        report.calculate_life_support_rate().unwrap(),
+        Some((string_filter(&strings, true) * string_filter(&strings, false)) as u128)
+    );
+
+    let mut group = c.benchmark_group("life_support_1m");
+    group.sample_size(10);
+    group.bench_function("strings", |b| {
+        b.iter(|| string_filter(&strings, true) * string_filter(&strings, false))
+    });
+    group.bench_function("bit_packed", |b| {
+        b.iter(|| report.calculate_life_support_rate().unwrap())
+    });
+    group.bench_function("bit_packed_with_parsing", |b| {
+        b.iter_batched(
+            LifeSupportReport::default,
+            |mut report| {
+                run(&mut input.as_bytes(), &mut report).unwrap();
+                report.calculate_life_support_rate().unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, life_support_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+mod common;
+
+use common::generate_input;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_3::{run, LifeSupportReport, PowerReport};
+
+/// Input sizes relative to the bundled `src/input.txt`.
+const SCALES: [usize; 3] = [1, 100, 10_000];
+
+fn solvers_benchmark(c: &mut Criterion) {
+    let bundled = include_str!("../src/input.txt").lines().count();
+    let mut group = c.benchmark_group("day_3");
+    group.sample_size(10);
+    for scale in SCALES {
+        let lines = bundled * scale;
+        let input = generate_input(lines);
+        let size = format!("{}x", scale);
+        group.throughput(Throughput::Elements(lines as u64));
+        group.bench_with_input(
+            BenchmarkId::new("power_report", &size),
+            &input,
+            |b, input| {
+                b.iter(|| {
+                    let mut report = PowerReport::default();
+                    run(&mut input.as_bytes(), &mut report).unwrap();
+                    report.get_power_consumption()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("life_support_report", &size),
+            &input,
+            |b, input| {
+                b.iter(|| {
+                    let mut report = LifeSupportReport::default();
+                    run(&mut input.as_bytes(), &mut report).unwrap();
+                    report.calculate_life_support_rate().unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solvers_benchmark);
+criterion_main!(benches);
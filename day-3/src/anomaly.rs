@@ -0,0 +1,192 @@
+use crate::{for_each_reading, PowerReport, ReadingError, RunOptions, Strictness};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+
+#[derive(Debug, PartialEq)]
+pub struct Anomaly {
+    pub line: usize,
+    pub reading: String,
+    pub score: f64,
+}
+
+/// Orders anomalies from the most anomalous one down, earlier lines first on equal
+/// scores, so the greatest one in a `BinaryHeap` is the first to drop out of the top.
+struct Ranked(Anomaly);
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .score
+            .total_cmp(&self.0.score)
+            .then(self.0.line.cmp(&other.0.line))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+impl PowerReport {
+    /// How unlikely the reading is given the column statistics: the sum over its
+    /// columns of the negative log-frequency of its digit, so rare digits in many
+    /// columns add up to a high score. Every digit is counted once more than it was
+    /// seen, so digits never seen in a column and empty reports still get a finite
+    /// score.
+    pub fn anomaly_score(&self, reading: &str) -> f64 {
+        let base = self.radix.base();
+        reading
+            .chars()
+            .zip(self.column_counts.iter())
+            .map(|(character, counts)| {
+                let digit = character.to_digit(base).expect("Cannot parse reading!");
+                let frequency = (counts[digit as usize] + 1) as f64 / (self.total + base) as f64;
+                -frequency.ln()
+            })
+            .sum()
+    }
+}
+
+/// Scores every reading against the statistics already gathered by `power` from the
+/// same readings and returns the `top` most anomalous ones, highest score first. Only
+/// the current top is kept in memory. Readings have to be as wide as the ones in
+/// `power`, and readings rejected in lenient mode aren't ranked.
+pub fn rank_anomalies<R: BufRead>(
+    readings: &mut R,
+    power: &PowerReport,
+    strictness: Strictness,
+    top: usize,
+) -> Result<Vec<Anomaly>, ReadingError> {
+    let options = RunOptions { strictness };
+    let width = Some(power.column_counts.len()).filter(|&width| width > 0);
+    let mut anomalies = BinaryHeap::with_capacity(top + 1);
+    for_each_reading(readings, power.radix, width, options, |line, reading| {
+        anomalies.push(Ranked(Anomaly {
+            line,
+            reading: reading.to_string(),
+            score: power.anomaly_score(reading),
+        }));
+        if anomalies.len() > top {
+            anomalies.pop();
+        }
+        Ok(())
+    })?;
+
+    Ok(anomalies
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Ranked(anomaly)| anomaly)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+
+    const INPUT: &str = "0000\n0001\n\n0000\n1111\n0101\n0000\n";
+
+    fn power_report() -> PowerReport {
+        let mut power = PowerReport::default();
+        run(&mut INPUT.as_bytes(), &mut power).unwrap();
+        power
+    }
+
+    #[test]
+    fn anomaly_score_sums_smoothed_negative_log_frequencies() {
+        let power = power_report();
+        let expected =
+            -(2.0f64 / 8.0).ln() - (3.0f64 / 8.0).ln() - (6.0f64 / 8.0).ln() - (4.0f64 / 8.0).ln();
+
+        assert!((power.anomaly_score("1100") - expected).abs() < 1e-9);
+        assert!(power.anomaly_score("1111") > power.anomaly_score("0101"));
+        assert!(power.anomaly_score("0101") > power.anomaly_score("0000"));
+    }
+
+    #[test]
+    fn rank_anomalies_returns_top_readings_with_line_numbers() {
+        let power = power_report();
+
+        let actual = rank_anomalies(&mut INPUT.as_bytes(), &power, Strictness::Strict, 2).unwrap();
+        let lines: Vec<(usize, &str)> = actual
+            .iter()
+            .map(|anomaly| (anomaly.line, anomaly.reading.as_str()))
+            .collect();
+        assert_eq!(lines, vec![(5, "1111"), (6, "0101")]);
+        assert!(actual[0].score > actual[1].score);
+    }
+
+    #[test]
+    fn rank_anomalies_reports_invalid_readings() {
+        let power = power_report();
+
+        let actual = rank_anomalies(
+            &mut "0000\n00x0\n".as_bytes(),
+            &power,
+            Strictness::Strict,
+            3,
+        );
+        assert_eq!(
+            actual,
+            Err(ReadingError::InvalidCharacter {
+                line: 2,
+                character: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn anomaly_score_is_finite_for_unseen_digits_and_empty_reports() {
+        let mut power = PowerReport::default();
+        run(&mut "00\n00\n".as_bytes(), &mut power).unwrap();
+        let expected = -2.0 * (1.0f64 / 4.0).ln();
+
+        assert!((power.anomaly_score("11") - expected).abs() < 1e-9);
+        assert_eq!(PowerReport::default().anomaly_score("11"), 0.0);
+    }
+
+    #[test]
+    fn rank_anomalies_keeps_earlier_lines_on_equal_scores() {
+        let power = power_report();
+
+        let actual = rank_anomalies(&mut INPUT.as_bytes(), &power, Strictness::Strict, 4).unwrap();
+        let lines: Vec<usize> = actual.iter().map(|anomaly| anomaly.line).collect();
+        assert_eq!(lines, vec![5, 6, 1, 2]);
+        assert!(
+            rank_anomalies(&mut INPUT.as_bytes(), &power, Strictness::Strict, 0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rank_anomalies_checks_width_against_power() {
+        let power = power_report();
+
+        let actual = rank_anomalies(
+            &mut "00000\n0000\n".as_bytes(),
+            &power,
+            Strictness::Strict,
+            3,
+        );
+        assert_eq!(
+            actual,
+            Err(ReadingError::InvalidWidth {
+                line: 1,
+                expected: 4,
+                found: 5
+            })
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    run_with_options, validate_reading, Radix, ReadingError, ReportParser, RunOptions, TieRule,
+};
+use std::io::{BufRead, Seek, SeekFrom};
+
+/// One of the life support digit criteria, narrowed down a column at a time. Only the
+/// chosen prefix and the counters of the current pass are kept in memory.
+struct DigitCriteria {
+    most_common: bool,
+    prefix: Vec<usize>,
+    candidates: usize,
+    counts: Vec<usize>,
+    last_candidate: u128,
+    rating: Option<u128>,
+}
+
+impl DigitCriteria {
+    fn new(radix: Radix, most_common: bool) -> DigitCriteria {
+        DigitCriteria {
+            most_common,
+            prefix: vec![],
+            candidates: 0,
+            counts: vec![0; radix.base() as usize],
+            last_candidate: 0,
+            rating: None,
+        }
+    }
+
+    fn count(&mut self, radix: Radix, reading: u128, width: usize) {
+        if self.rating.is_some() {
+            return;
+        }
+        let matches = self
+            .prefix
+            .iter()
+            .enumerate()
+            .all(|(column, &digit)| radix.digit(reading, width, column) == digit);
+        if !matches {
+            return;
+        }
+        self.candidates += 1;
+        self.last_candidate = reading;
+        self.counts[radix.digit(reading, width, self.prefix.len())] += 1;
+    }
+
+    /// Fails when no reading made it into the pass, which only happens when the report
+    /// has no valid reading at all.
+    fn finish_column(
+        &mut self,
+        radix: Radix,
+        tie_rule: TieRule,
+        width: usize,
+    ) -> Result<(), ReadingError> {
+        if self.rating.is_some() {
+            return Ok(());
+        }
+        match self.candidates {
+            0 => return Err(ReadingError::EmptyReport),
+            1 => self.rating = Some(radix.value(self.last_candidate, width)),
+            _ => {
+                let present = self
+                    .counts
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .filter(|&(_, count)| count > 0);
+                self.prefix.push(tie_rule.select(present, self.most_common));
+                if self.prefix.len() == width {
+                    self.rating = Some(radix.value_of_digits(self.prefix.iter().copied()));
+                }
+            }
+        }
+        self.candidates = 0;
+        self.counts.iter_mut().for_each(|count| *count = 0);
+
+        Ok(())
+    }
+}
+
+struct ColumnPass {
+    radix: Radix,
+    width: usize,
+    oxygen: DigitCriteria,
+    co2: DigitCriteria,
+}
+
+impl ReportParser for ColumnPass {
+    fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        let width = if self.width == 0 {
+            reading.len()
+        } else {
+            self.width
+        };
+        validate_reading(reading, self.radix, width, line)
+    }
+
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        self.check_reading(line, reading)?;
+        if self.width == 0 {
+            self.width = reading.len();
+        }
+        let reading = self.radix.pack(reading);
+        self.oxygen.count(self.radix, reading, self.width);
+        self.co2.count(self.radix, reading, self.width);
+
+        Ok(())
+    }
+}
+
+/// Computes the oxygen generator and CO2 scrubber ratings without buffering the
+/// readings: the report is read again from the start for every column, so memory use
+/// doesn't depend on its length. Takes at most `width` passes. In lenient mode the
+/// invalid readings are skipped on every pass. Fails with `ReadingError::EmptyReport`
+/// when no reading is left to rate.
+pub fn bounded_life_support_ratings<R: BufRead + Seek>(
+    readings: &mut R,
+    radix: Radix,
+    options: RunOptions,
+    tie_rule: TieRule,
+) -> Result<(u128, u128), ReadingError> {
+    let mut pass = ColumnPass {
+        radix,
+        width: 0,
+        oxygen: DigitCriteria::new(radix, true),
+        co2: DigitCriteria::new(radix, false),
+    };
+    while pass.oxygen.rating.is_none() || pass.co2.rating.is_none() {
+        readings
+            .seek(SeekFrom::Start(0))
+            .expect("Cannot rewind readings!");
+        run_with_options(readings, options, &mut pass)?;
+        pass.oxygen.finish_column(radix, tie_rule, pass.width)?;
+        pass.co2.finish_column(radix, tie_rule, pass.width)?;
+    }
+
+    Ok((pass.oxygen.rating.unwrap(), pass.co2.rating.unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, LifeSupportReport, RatingStrategy, Strictness};
+    use input::testing::xorshift;
+    use std::io::Cursor;
+    use test_case::test_case;
+
+    #[test]
+    fn bounded_ratings_match_example() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+        let actual = bounded_life_support_ratings(
+            &mut Cursor::new(input),
+            Radix::BINARY,
+            RunOptions::default(),
+            TieRule::Standard,
+        )
+        .unwrap();
+        assert_eq!(actual, (23, 10));
+    }
+
+    #[test]
+    fn bounded_ratings_match_in_memory_report() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for width in [1, 3, 12, 40] {
+            let mut input = String::new();
+            for _ in 0..500 {
+                input.push_str(&format!(
+                    "{:0width$b}\n",
+                    xorshift(&mut state) >> (64 - width),
+                    width = width
+                ));
+            }
+            let mut report = LifeSupportReport::default();
+            run(&mut input.as_bytes(), &mut report).unwrap();
+
+            let actual = bounded_life_support_ratings(
+                &mut Cursor::new(&input),
+                Radix::BINARY,
+                RunOptions::default(),
+                TieRule::Standard,
+            )
+            .unwrap();
+            assert_eq!(
+                actual,
+                (
+                    report.get_oxygen_gen_rate().unwrap(),
+                    report.get_co2_scrubber_rate().unwrap()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn bounded_ratings_report_invalid_readings() {
+        let input = "00100\n11110\n1011\n";
+
+        let actual = bounded_life_support_ratings(
+            &mut Cursor::new(input),
+            Radix::BINARY,
+            RunOptions::default(),
+            TieRule::Standard,
+        );
+        assert_eq!(
+            actual,
+            Err(ReadingError::InvalidWidth {
+                line: 3,
+                expected: 5,
+                found: 4
+            })
+        );
+    }
+
+    #[test]
+    fn bounded_ratings_match_in_memory_report_with_radix() {
+        let radix = Radix::new(5).unwrap();
+        let input = "0421\n4403\n1234\n4410\n0044\n4321\n";
+        let mut report = LifeSupportReport::new(radix, TieRule::Highest, RatingStrategy::Filter);
+        run(&mut input.as_bytes(), &mut report).unwrap();
+
+        let actual = bounded_life_support_ratings(
+            &mut Cursor::new(input),
+            radix,
+            RunOptions::default(),
+            TieRule::Highest,
+        )
+        .unwrap();
+        assert_eq!(
+            actual,
+            (
+                report.get_oxygen_gen_rate().unwrap(),
+                report.get_co2_scrubber_rate().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn bounded_ratings_skip_invalid_readings_when_lenient() {
+        let input = "00100\n1111\n11110\n10110\n1x111\n10111\n";
+        let options = RunOptions {
+            strictness: Strictness::Lenient,
+        };
+
+        let actual = bounded_life_support_ratings(
+            &mut Cursor::new(input),
+            Radix::BINARY,
+            options,
+            TieRule::Standard,
+        )
+        .unwrap();
+        assert_eq!(actual, (0b10111, 0b00100));
+    }
+
+    #[test_case("")]
+    #[test_case("\n\n")]
+    #[test_case("1x\n2\n")]
+    fn bounded_ratings_reject_report_without_valid_readings(input: &str) {
+        let options = RunOptions {
+            strictness: Strictness::Lenient,
+        };
+
+        let actual = bounded_life_support_ratings(
+            &mut Cursor::new(input),
+            Radix::BINARY,
+            options,
+            TieRule::Standard,
+        );
+        assert_eq!(actual, Err(ReadingError::EmptyReport));
+    }
+}
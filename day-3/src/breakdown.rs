@@ -0,0 +1,292 @@
+use crate::{LifeSupportReport, PowerReport};
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ColumnBreakdown {
+    pub column: usize,
+    pub counts: Vec<u32>,
+    pub gamma_digit: char,
+    pub gamma_percentage: f64,
+    pub epsilon_digit: char,
+    pub oxygen_candidates: usize,
+    pub co2_candidates: usize,
+}
+
+/// Per-column statistics behind the power consumption and life support ratings.
+/// Columns are numbered from the most significant digit, `counts` holds how often each
+/// digit appears, and the candidate counts are the readings left in each life support
+/// filter once that column has been applied.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Breakdown {
+    pub radix: u32,
+    pub total: u32,
+    pub columns: Vec<ColumnBreakdown>,
+}
+
+fn to_char(digit: usize, radix: u32) -> char {
+    std::char::from_digit(digit as u32, radix).expect("Digit is outside of the radix!")
+}
+
+/// Both reports have to be filled from the same readings, so they agree on the radix,
+/// the width and the number of readings.
+fn check_reports(power: &PowerReport, life_support: &LifeSupportReport) -> Result<(), String> {
+    if power.radix != life_support.radix {
+        return Err(format!(
+            "Reports use radix {} and {}!",
+            power.radix.base(),
+            life_support.radix.base()
+        ));
+    }
+    if power.column_counts.len() != life_support.width {
+        return Err(format!(
+            "Reports have {} and {} columns!",
+            power.column_counts.len(),
+            life_support.width
+        ));
+    }
+    if power.total as usize != life_support.readings.len() {
+        return Err(format!(
+            "Reports hold {} and {} readings!",
+            power.total,
+            life_support.readings.len()
+        ));
+    }
+    if power.total == 0 {
+        return Err("Cannot break down an empty report!".to_string());
+    }
+
+    Ok(())
+}
+
+impl Breakdown {
+    pub fn build(
+        power: &PowerReport,
+        life_support: &LifeSupportReport,
+    ) -> Result<Breakdown, String> {
+        check_reports(power, life_support)?;
+        let radix = power.radix.base();
+        let (_, oxygen_candidates) = life_support
+            .filter_by_digit_criteria(true)
+            .map_err(|error| error.to_string())?;
+        let (_, co2_candidates) = life_support
+            .filter_by_digit_criteria(false)
+            .map_err(|error| error.to_string())?;
+        let columns = power
+            .column_counts
+            .iter()
+            .zip(oxygen_candidates.iter().zip(co2_candidates.iter()))
+            .enumerate()
+            .map(|(column, (counts, (&oxygen, &co2)))| {
+                let gamma_digit = power.column_digit(counts, true);
+                ColumnBreakdown {
+                    column,
+                    counts: counts.clone(),
+                    gamma_digit: to_char(gamma_digit, radix),
+                    gamma_percentage: counts[gamma_digit] as f64 * 100.0 / power.total as f64,
+                    epsilon_digit: to_char(power.column_digit(counts, false), radix),
+                    oxygen_candidates: oxygen,
+                    co2_candidates: co2,
+                }
+            })
+            .collect();
+
+        Ok(Breakdown {
+            radix,
+            total: power.total,
+            columns,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Cannot serialize breakdown!")
+    }
+
+    fn headers(&self) -> Vec<String> {
+        let digits = (0..self.radix as usize).map(|digit| to_char(digit, self.radix).to_string());
+        let statistics = ["gamma", "gamma %", "epsilon", "oxygen", "co2"].map(String::from);
+
+        std::iter::once("column".to_string())
+            .chain(digits)
+            .chain(statistics)
+            .collect()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.columns
+            .iter()
+            .map(|column| {
+                let counts = column.counts.iter().map(|count| count.to_string());
+                let statistics = [
+                    column.gamma_digit.to_string(),
+                    format!("{:.1}", column.gamma_percentage),
+                    column.epsilon_digit.to_string(),
+                    column.oxygen_candidates.to_string(),
+                    column.co2_candidates.to_string(),
+                ];
+
+                std::iter::once(column.column.to_string())
+                    .chain(counts)
+                    .chain(statistics)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let headers = self.headers();
+        let rows = self.rows();
+        let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let format_row = |row: &[String]| -> String {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, &width)| format!("{:>width$}", cell, width = width))
+                .collect();
+            cells.join(" | ")
+        };
+        let separator: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        writeln!(f, "{}", format_row(&headers))?;
+        writeln!(f, "{}", separator.join("-+-"))?;
+        for row in rows {
+            writeln!(f, "{}", format_row(&row))?;
+        }
+        write!(f, "{} readings", self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, Radix, RatingStrategy, TieRule};
+
+    const EXAMPLE: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    fn example_breakdown() -> Breakdown {
+        let mut power = PowerReport::default();
+        let mut life_support = LifeSupportReport::default();
+        run(&mut EXAMPLE.as_bytes(), &mut power).unwrap();
+        run(&mut EXAMPLE.as_bytes(), &mut life_support).unwrap();
+
+        Breakdown::build(&power, &life_support).unwrap()
+    }
+
+    #[test]
+    fn build_returns_statistics_per_column() {
+        let breakdown = example_breakdown();
+        let ones: Vec<u32> = breakdown.columns.iter().map(|c| c.counts[1]).collect();
+        let gamma: String = breakdown.columns.iter().map(|c| c.gamma_digit).collect();
+        let epsilon: String = breakdown.columns.iter().map(|c| c.epsilon_digit).collect();
+        let oxygen: Vec<usize> = breakdown
+            .columns
+            .iter()
+            .map(|c| c.oxygen_candidates)
+            .collect();
+        let co2: Vec<usize> = breakdown.columns.iter().map(|c| c.co2_candidates).collect();
+
+        assert_eq!(breakdown.total, 12);
+        assert_eq!(ones, vec![7, 5, 8, 7, 5]);
+        assert_eq!(breakdown.columns[2].counts[0], 4);
+        assert_eq!(breakdown.columns[1].gamma_percentage, 7.0 * 100.0 / 12.0);
+        assert_eq!(gamma, "10110");
+        assert_eq!(epsilon, "01001");
+        assert_eq!(oxygen, vec![7, 4, 3, 2, 1]);
+        assert_eq!(co2, vec![5, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn display_renders_table() {
+        let expected = "\
+column | 0 | 1 | gamma | gamma % | epsilon | oxygen | co2
+-------+---+---+-------+---------+---------+--------+----
+     0 | 5 | 7 |     1 |    58.3 |       0 |      7 |   5
+     1 | 7 | 5 |     0 |    58.3 |       1 |      4 |   2
+     2 | 4 | 8 |     1 |    66.7 |       0 |      3 |   1
+     3 | 5 | 7 |     1 |    58.3 |       0 |      2 |   1
+     4 | 7 | 5 |     0 |    58.3 |       1 |      1 |   1
+12 readings";
+
+        assert_eq!(example_breakdown().to_string(), expected);
+    }
+
+    #[test]
+    fn to_json_serializes_columns() {
+        let json: serde_json::Value = serde_json::from_str(&example_breakdown().to_json()).unwrap();
+
+        assert_eq!(json["radix"], 2);
+        assert_eq!(json["total"], 12);
+        assert_eq!(json["columns"].as_array().unwrap().len(), 5);
+        assert_eq!(json["columns"][0]["counts"], serde_json::json!([5, 7]));
+        assert_eq!(json["columns"][0]["gamma_digit"], "1");
+        assert_eq!(json["columns"][1]["oxygen_candidates"], 4);
+    }
+
+    #[test]
+    fn display_renders_column_per_digit() {
+        let radix = Radix::new(4).unwrap();
+        let input = "03\n13\n12\n";
+        let mut power = PowerReport::new(radix, TieRule::Standard);
+        let mut life_support =
+            LifeSupportReport::new(radix, TieRule::Standard, RatingStrategy::Filter);
+        run(&mut input.as_bytes(), &mut (&mut power, &mut life_support)).unwrap();
+        let expected = "\
+column | 0 | 1 | 2 | 3 | gamma | gamma % | epsilon | oxygen | co2
+-------+---+---+---+---+-------+---------+---------+--------+----
+     0 | 1 | 2 | 0 | 0 |     1 |    66.7 |       2 |      2 |   1
+     1 | 0 | 0 | 1 | 2 |     3 |    66.7 |       0 |      1 |   1
+3 readings";
+
+        assert_eq!(
+            Breakdown::build(&power, &life_support).unwrap().to_string(),
+            expected
+        );
+    }
+
+    fn reports(radix: Radix, input: &str) -> (PowerReport, LifeSupportReport) {
+        let mut power = PowerReport::new(radix, TieRule::Standard);
+        let mut life_support =
+            LifeSupportReport::new(radix, TieRule::Standard, RatingStrategy::Filter);
+        run(&mut input.as_bytes(), &mut (&mut power, &mut life_support)).unwrap();
+        (power, life_support)
+    }
+
+    #[test]
+    fn build_rejects_empty_reports() {
+        let (power, life_support) = reports(Radix::BINARY, "\n");
+
+        assert_eq!(
+            Breakdown::build(&power, &life_support),
+            Err("Cannot break down an empty report!".to_string())
+        );
+    }
+
+    #[test]
+    fn build_rejects_mismatched_reports() {
+        let (power, _) = reports(Radix::BINARY, "0110\n1100\n");
+        let (_, narrow) = reports(Radix::BINARY, "011\n110\n");
+        let (_, short) = reports(Radix::BINARY, "0110\n");
+        let (_, quaternary) = reports(Radix::new(4).unwrap(), "0110\n1100\n");
+
+        assert_eq!(
+            Breakdown::build(&power, &narrow),
+            Err("Reports have 4 and 3 columns!".to_string())
+        );
+        assert_eq!(
+            Breakdown::build(&power, &short),
+            Err("Reports hold 2 and 1 readings!".to_string())
+        );
+        assert_eq!(
+            Breakdown::build(&power, &quaternary),
+            Err("Reports use radix 2 and 4!".to_string())
+        );
+    }
+}
@@ -0,0 +1,910 @@
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+mod anomaly;
+mod bounded;
+mod breakdown;
+mod radix;
+mod trie;
+
+pub use anomaly::{rank_anomalies, Anomaly};
+pub use bounded::bounded_life_support_ratings;
+pub use breakdown::{Breakdown, ColumnBreakdown};
+pub use radix::{Radix, TieRule};
+pub use trie::ReadingTrie;
+
+#[derive(Default)]
+pub struct PowerReport {
+    radix: Radix,
+    tie_rule: TieRule,
+    total: u32,
+    column_counts: Vec<Vec<u32>>,
+}
+
+pub trait ReportParser {
+    /// Radix the report reads its digits in.
+    fn radix(&self) -> Radix;
+
+    /// Returns the error `update_report` would reject the reading found on `line` with,
+    /// without adding it.
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError>;
+
+    /// Adds the reading found on `line` to the report, leaving the report untouched
+    /// when the reading is rejected.
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError>;
+}
+
+impl<T: ReportParser + ?Sized> ReportParser for &mut T {
+    fn radix(&self) -> Radix {
+        (**self).radix()
+    }
+
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        (**self).check_reading(line, reading)
+    }
+
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        (**self).update_report(line, reading)
+    }
+}
+
+impl<T: ReportParser + ?Sized> ReportParser for Box<T> {
+    fn radix(&self) -> Radix {
+        (**self).radix()
+    }
+
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        (**self).check_reading(line, reading)
+    }
+
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        (**self).update_report(line, reading)
+    }
+}
+
+/// Fans every reading out to all the reports, so a single `run` fills them together.
+/// A reading is checked against every report first, so one rejected by any of them
+/// leaves all of them untouched. The radix is the first report's, and every report
+/// still checks the digits against its own.
+impl<T: ReportParser> ReportParser for Vec<T> {
+    fn radix(&self) -> Radix {
+        self.first()
+            .map_or_else(Radix::default, ReportParser::radix)
+    }
+
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        self.iter()
+            .try_for_each(|report| report.check_reading(line, reading))
+    }
+
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        self.check_reading(line, reading)?;
+        for report in self.iter_mut() {
+            report.update_report(line, reading)?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_report_parser_for_tuple {
+    ($($report:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($report: ReportParser),+> ReportParser for ($($report,)+) {
+            fn radix(&self) -> Radix {
+                self.0.radix()
+            }
+
+            fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+                let ($($report,)+) = self;
+                $($report.check_reading(line, reading)?;)+
+                Ok(())
+            }
+
+            fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+                self.check_reading(line, reading)?;
+                let ($($report,)+) = self;
+                $($report.update_report(line, reading)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_report_parser_for_tuple!(A, B);
+impl_report_parser_for_tuple!(A, B, C);
+impl_report_parser_for_tuple!(A, B, C, D);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReadingError {
+    TooWide {
+        line: usize,
+        width: usize,
+    },
+    InvalidWidth {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCharacter {
+        line: usize,
+        character: char,
+    },
+    /// No valid reading to rate, because the report is empty or every line was rejected.
+    EmptyReport,
+}
+
+impl fmt::Display for ReadingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadingError::TooWide { line, width } => {
+                write!(f, "line {}: {} digits don't fit in 128 bits", line, width)
+            }
+            ReadingError::InvalidWidth {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} digits but found {}",
+                line, expected, found
+            ),
+            ReadingError::InvalidCharacter { line, character } => {
+                write!(f, "line {}: invalid character {:?}", line, character)
+            }
+            ReadingError::EmptyReport => write!(f, "the report has no valid readings"),
+        }
+    }
+}
+
+impl Error for ReadingError {}
+
+impl PowerReport {
+    pub fn new(radix: Radix, tie_rule: TieRule) -> PowerReport {
+        PowerReport {
+            radix,
+            tie_rule,
+            ..Default::default()
+        }
+    }
+
+    fn column_digit(&self, counts: &[u32], most_common: bool) -> usize {
+        let counts = counts.iter().map(|&count| count as usize).enumerate();
+        self.tie_rule.select(counts, most_common)
+    }
+
+    fn rate(&self, most_common: bool) -> u128 {
+        self.radix.value_of_digits(
+            self.column_counts
+                .iter()
+                .map(|counts| self.column_digit(counts, most_common)),
+        )
+    }
+
+    /// Spells the most common digit of every column.
+    pub fn get_gamma_rate(&self) -> u128 {
+        self.rate(true)
+    }
+
+    /// Spells the least common digit of every column.
+    pub fn get_epsilon_rate(&self) -> u128 {
+        self.rate(false)
+    }
+
+    /// Returns `None` when the product doesn't fit in 128 bits.
+    pub fn get_power_consumption(&self) -> Option<u128> {
+        self.get_gamma_rate().checked_mul(self.get_epsilon_rate())
+    }
+}
+
+impl ReportParser for PowerReport {
+    fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        let width = if self.column_counts.is_empty() {
+            reading.len()
+        } else {
+            self.column_counts.len()
+        };
+        validate_reading(reading, self.radix, width, line)
+    }
+
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        self.check_reading(line, reading)?;
+        if self.column_counts.is_empty() {
+            self.column_counts = vec![vec![0; self.radix.base() as usize]; reading.len()];
+        }
+
+        self.total += 1;
+        for (counts, character) in self.column_counts.iter_mut().zip(reading.chars()) {
+            counts[character.to_digit(self.radix.base()).unwrap() as usize] += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RatingStrategy {
+    #[default]
+    Filter,
+    Trie,
+}
+
+impl FromStr for RatingStrategy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "filter" => Ok(RatingStrategy::Filter),
+            "trie" => Ok(RatingStrategy::Trie),
+            _ => Err(format!("Unknown rating strategy {}!", name)),
+        }
+    }
+}
+
+/// Whether the life support ratings are computed from readings kept in memory or by
+/// re-reading the report with `bounded_life_support_ratings`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MemoryMode {
+    #[default]
+    InMemory,
+    Bounded,
+}
+
+impl FromStr for MemoryMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "in-memory" => Ok(MemoryMode::InMemory),
+            "bounded" => Ok(MemoryMode::Bounded),
+            _ => Err(format!("Unknown memory mode {}!", name)),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LifeSupportReport {
+    radix: Radix,
+    tie_rule: TieRule,
+    width: usize,
+    readings: Vec<u128>,
+    strategy: RatingStrategy,
+}
+
+impl LifeSupportReport {
+    pub fn new(radix: Radix, tie_rule: TieRule, strategy: RatingStrategy) -> LifeSupportReport {
+        LifeSupportReport {
+            radix,
+            tie_rule,
+            strategy,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_strategy(strategy: RatingStrategy) -> LifeSupportReport {
+        LifeSupportReport {
+            strategy,
+            ..Default::default()
+        }
+    }
+
+    fn build_trie(&self) -> ReadingTrie {
+        ReadingTrie::build(self.radix, self.width, &self.readings)
+    }
+
+    fn check_not_empty(&self) -> Result<(), ReadingError> {
+        if self.readings.is_empty() {
+            return Err(ReadingError::EmptyReport);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the rating together with the number of candidates left after each column.
+    fn filter_by_digit_criteria(
+        &self,
+        most_common: bool,
+    ) -> Result<(u128, Vec<usize>), ReadingError> {
+        self.check_not_empty()?;
+        let mut readings = self.readings.clone();
+        let mut survivors = Vec::with_capacity(self.width);
+        for column in 0..self.width {
+            let digit = |reading: u128| self.radix.digit(reading, self.width, column);
+            if readings.len() > 1 {
+                let mut counts = vec![0; self.radix.base() as usize];
+                for &reading in &readings {
+                    counts[digit(reading)] += 1;
+                }
+                let present = counts
+                    .into_iter()
+                    .enumerate()
+                    .filter(|&(_, count)| count > 0);
+                let keep = self.tie_rule.select(present, most_common);
+                readings.retain(|&reading| digit(reading) == keep);
+            }
+            survivors.push(readings.len());
+        }
+
+        Ok((self.radix.value(readings[0], self.width), survivors))
+    }
+
+    /// Fails with `ReadingError::EmptyReport` when there is no reading to rate.
+    pub fn get_oxygen_gen_rate(&self) -> Result<u128, ReadingError> {
+        match self.strategy {
+            RatingStrategy::Filter => Ok(self.filter_by_digit_criteria(true)?.0),
+            RatingStrategy::Trie => {
+                self.check_not_empty()?;
+                Ok(self.build_trie().get_oxygen_gen_rate(self.tie_rule))
+            }
+        }
+    }
+
+    /// Fails with `ReadingError::EmptyReport` when there is no reading to rate.
+    pub fn get_co2_scrubber_rate(&self) -> Result<u128, ReadingError> {
+        match self.strategy {
+            RatingStrategy::Filter => Ok(self.filter_by_digit_criteria(false)?.0),
+            RatingStrategy::Trie => {
+                self.check_not_empty()?;
+                Ok(self.build_trie().get_co2_scrubber_rate(self.tie_rule))
+            }
+        }
+    }
+
+    /// Fails on an empty report like the ratings, and returns `None` when the product
+    /// doesn't fit in 128 bits.
+    pub fn calculate_life_support_rate(&self) -> Result<Option<u128>, ReadingError> {
+        match self.strategy {
+            RatingStrategy::Filter => Ok(self
+                .get_oxygen_gen_rate()?
+                .checked_mul(self.get_co2_scrubber_rate()?)),
+            RatingStrategy::Trie => {
+                self.check_not_empty()?;
+                let trie = self.build_trie();
+                Ok(trie
+                    .get_oxygen_gen_rate(self.tie_rule)
+                    .checked_mul(trie.get_co2_scrubber_rate(self.tie_rule)))
+            }
+        }
+    }
+}
+
+impl ReportParser for LifeSupportReport {
+    fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    fn check_reading(&self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        let width = if self.readings.is_empty() {
+            reading.len()
+        } else {
+            self.width
+        };
+        validate_reading(reading, self.radix, width, line)
+    }
+
+    fn update_report(&mut self, line: usize, reading: &str) -> Result<(), ReadingError> {
+        self.check_reading(line, reading)?;
+        if self.readings.is_empty() {
+            self.width = reading.len();
+        }
+        self.readings.push(self.radix.pack(reading));
+
+        Ok(())
+    }
+}
+
+fn validate_reading(
+    reading: &str,
+    radix: Radix,
+    width: usize,
+    line: usize,
+) -> Result<(), ReadingError> {
+    if reading.len() > radix.max_width() {
+        return Err(ReadingError::TooWide {
+            line,
+            width: reading.len(),
+        });
+    }
+    if let Some(character) = reading.chars().find(|&c| !radix.is_digit(c)) {
+        return Err(ReadingError::InvalidCharacter { line, character });
+    }
+    if reading.len() != width {
+        return Err(ReadingError::InvalidWidth {
+            line,
+            expected: width,
+            found: reading.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// What `run_with_options` does with a reading that doesn't validate.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Strictness {
+    /// Stops at the first invalid reading.
+    #[default]
+    Strict,
+    /// Skips invalid readings and returns them once the whole report has been read.
+    Lenient,
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "strict" => Ok(Strictness::Strict),
+            "lenient" => Ok(Strictness::Lenient),
+            _ => Err(format!("Unknown strictness {}!", name)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunOptions {
+    pub strictness: Strictness,
+}
+
+/// Feeds every reading to the report, stopping at the first invalid one.
+pub fn run<R: BufRead, T: ReportParser>(
+    readings: &mut R,
+    report: &mut T,
+) -> Result<(), ReadingError> {
+    run_with_options(readings, RunOptions::default(), report).map(|_| ())
+}
+
+/// Feeds every reading to the report, checking its digits in the report's radix. The
+/// width of the first valid reading is taken as the width of the whole report, blank
+/// lines are skipped and `\r\n` line endings accepted. In lenient mode the rejected
+/// readings are returned instead of stopping the run.
+pub fn run_with_options<R: BufRead, T: ReportParser>(
+    readings: &mut R,
+    options: RunOptions,
+    report: &mut T,
+) -> Result<Vec<ReadingError>, ReadingError> {
+    let radix = report.radix();
+    for_each_reading(readings, radix, None, options, |line, reading| {
+        report.update_report(line, reading)
+    })
+}
+
+/// Validates every reading and hands it over together with its line number. Readings
+/// have to be `width` digits wide, or as wide as the first valid one without it.
+fn for_each_reading<R, F>(
+    readings: &mut R,
+    radix: Radix,
+    mut width: Option<usize>,
+    options: RunOptions,
+    mut consume: F,
+) -> Result<Vec<ReadingError>, ReadingError>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> Result<(), ReadingError>,
+{
+    let mut rejected = vec![];
+    for (line, reading) in input::lines(readings) {
+        if reading.is_empty() {
+            continue;
+        }
+        let expected = width.unwrap_or(reading.len());
+        let result =
+            validate_reading(&reading, radix, expected, line).and_then(|_| consume(line, &reading));
+        match (result, options.strictness) {
+            (Ok(()), _) => width = Some(expected),
+            (Err(error), Strictness::Lenient) => rejected.push(error),
+            (Err(error), Strictness::Strict) => return Err(error),
+        }
+    }
+
+    Ok(rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn binary_report(total: u32, column_sums: Vec<u32>) -> PowerReport {
+        PowerReport {
+            total,
+            column_counts: column_sums
+                .into_iter()
+                .map(|ones| vec![total.saturating_sub(ones), ones])
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn ones(report: &PowerReport) -> Vec<u32> {
+        report
+            .column_counts
+            .iter()
+            .map(|counts| counts[1])
+            .collect()
+    }
+
+    #[test_case(12, [7, 5, 7, 7, 4].to_vec(), 0b10110)]
+    #[test_case(4, [3, 0, 2, 4].to_vec(), 0b1011)]
+    #[test_case(5, [3, 1, 2, 4, 5].to_vec(), 0b10011)]
+    fn test_get_gamma_rate_for_report(total: u32, column_sums: Vec<u32>, expected: u128) {
+        let report = binary_report(total, column_sums);
+        let actual = report.get_gamma_rate();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(12, [7, 5, 7, 7, 4].to_vec(), 0b01001)]
+    #[test_case(4, [3, 0, 2, 4].to_vec(), 0b0100)]
+    #[test_case(5, [3, 1, 2, 4, 5].to_vec(), 0b01100)]
+    fn test_get_epsilon_rate_for_report(total: u32, column_sums: Vec<u32>, expected: u128) {
+        let report = binary_report(total, column_sums);
+        let actual = report.get_epsilon_rate();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(12, [7, 5, 7, 7, 4].to_vec(), 22*9)]
+    #[test_case(4, [3, 0, 2, 4].to_vec(), 11*4)]
+    #[test_case(5, [3, 1, 2, 4, 5].to_vec(), 19*12)]
+    fn test_get_power_consumption_for_report(total: u32, column_sums: Vec<u32>, expected: u128) {
+        let report = binary_report(total, column_sums);
+        let actual = report.get_power_consumption();
+
+        assert_eq!(actual, Some(expected));
+    }
+
+    #[test_case([12, 4, 0, 2, 11, 0, 9, 8, 8, 0].to_vec(), "0110100111", [12, 5, 1, 2, 12, 0, 9, 9, 9, 1].to_vec())]
+    fn test_update_report(initial_state: Vec<u32>, reading: &str, expected: Vec<u32>) {
+        let mut actual = binary_report(2, initial_state);
+        actual.update_report(3, reading).unwrap();
+
+        assert_eq!(actual.total, 3, "The total value is not equal");
+        for (actual_bit, expected_bit) in ones(&actual).iter().zip(expected.iter()) {
+            assert_eq!(actual_bit, expected_bit, "The column sum doesn't match");
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+        let mut report = PowerReport::default();
+
+        run(&mut input.as_bytes(), &mut report).unwrap();
+        let actual = report.get_power_consumption();
+        assert_eq!(actual, Some(198));
+    }
+
+    #[test_case("00100\r\n11110\r\n10110\r\n" ; "crlf line endings")]
+    #[test_case("00100\n11110\n10110" ; "no trailing newline")]
+    #[test_case("\n00100\n11110\n\n10110\n\n" ; "blank lines")]
+    fn test_run_detects_width(input: &str) {
+        let mut report = PowerReport::default();
+
+        run(&mut input.as_bytes(), &mut report).unwrap();
+        assert_eq!(report.total, 3);
+        assert_eq!(ones(&report), vec![2, 1, 3, 2, 0]);
+    }
+
+    #[test_case("00100\n1111\n" => ReadingError::InvalidWidth { line: 2, expected: 5, found: 4 })]
+    #[test_case("00100\n\n111100\n" => ReadingError::InvalidWidth { line: 3, expected: 5, found: 6 })]
+    #[test_case("00100\n11210\n" => ReadingError::InvalidCharacter { line: 2, character: '2' })]
+    #[test_case("0 100\n" => ReadingError::InvalidCharacter { line: 1, character: ' ' })]
+    #[test_case(&"1".repeat(129) => ReadingError::TooWide { line: 1, width: 129 })]
+    fn test_run_rejects_invalid_reading(input: &str) -> ReadingError {
+        let mut report = PowerReport::default();
+
+        run(&mut input.as_bytes(), &mut report).unwrap_err()
+    }
+
+    #[test_case("00100", "1x110" => ReadingError::InvalidCharacter { line: 2, character: 'x' })]
+    #[test_case("00100", "1110" => ReadingError::InvalidWidth { line: 2, expected: 5, found: 4 })]
+    fn test_update_report_rejects_reading_untouched(first: &str, second: &str) -> ReadingError {
+        let mut power = PowerReport::default();
+        let mut life_support = LifeSupportReport::default();
+        power.update_report(1, first).unwrap();
+        life_support.update_report(1, first).unwrap();
+
+        let error = power.update_report(2, second).unwrap_err();
+        assert_eq!(life_support.update_report(2, second), Err(error.clone()));
+        assert_eq!(power.total, 1);
+        assert_eq!(ones(&power), vec![0, 0, 1, 0, 0]);
+        assert_eq!(life_support.readings, vec![0b00100]);
+        error
+    }
+
+    #[test]
+    fn test_run_with_options_skips_invalid_readings_when_lenient() {
+        let input = "0x100\n00100\n1111\n11110\n\n10210\n10110\n";
+        let options = RunOptions {
+            strictness: Strictness::Lenient,
+        };
+        let mut report = PowerReport::default();
+
+        let rejected = run_with_options(&mut input.as_bytes(), options, &mut report).unwrap();
+        assert_eq!(
+            rejected,
+            vec![
+                ReadingError::InvalidCharacter {
+                    line: 1,
+                    character: 'x'
+                },
+                ReadingError::InvalidWidth {
+                    line: 3,
+                    expected: 5,
+                    found: 4
+                },
+                ReadingError::InvalidCharacter {
+                    line: 6,
+                    character: '2'
+                },
+            ]
+        );
+        assert_eq!(report.total, 3);
+        assert_eq!(ones(&report), vec![2, 1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn test_run_with_options_stops_at_first_invalid_reading_when_strict() {
+        let input = "00100\n1111\n11110\n10210\n";
+        let mut report = PowerReport::default();
+
+        let actual = run_with_options(&mut input.as_bytes(), RunOptions::default(), &mut report);
+        assert_eq!(
+            actual,
+            Err(ReadingError::InvalidWidth {
+                line: 2,
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(report.total, 1);
+    }
+
+    #[test_case("strict" => Ok(Strictness::Strict))]
+    #[test_case("lenient" => Ok(Strictness::Lenient))]
+    #[test_case("loose" => Err("Unknown strictness loose!".to_string()))]
+    fn test_strictness_from_str(name: &str) -> Result<Strictness, String> {
+        name.parse()
+    }
+
+    #[test]
+    fn test_life_support_update_report_parses_reading() {
+        let mut report = LifeSupportReport::default();
+        report.update_report(1, "00100").unwrap();
+        report.update_report(2, "11110").unwrap();
+
+        assert_eq!(report.width, 5);
+        assert_eq!(report.readings, vec![0b00100, 0b11110]);
+    }
+
+    fn example_report(strategy: RatingStrategy) -> LifeSupportReport {
+        LifeSupportReport {
+            width: 5,
+            readings: vec![
+                0b00100, 0b11110, 0b10110, 0b10111, 0b10101, 0b01111, 0b00111, 0b11100, 0b10000,
+                0b11001, 0b00010, 0b01010,
+            ],
+            strategy,
+            ..Default::default()
+        }
+    }
+
+    #[test_case(RatingStrategy::Filter)]
+    #[test_case(RatingStrategy::Trie)]
+    fn test_get_oxygen_gen_rate(strategy: RatingStrategy) {
+        let report = example_report(strategy);
+        let expected = 23;
+        let actual = report.get_oxygen_gen_rate();
+
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[test_case(RatingStrategy::Filter)]
+    #[test_case(RatingStrategy::Trie)]
+    fn test_get_co2_scrubber_rate(strategy: RatingStrategy) {
+        let report = example_report(strategy);
+        let expected = 10;
+        let actual = report.get_co2_scrubber_rate();
+
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[test_case(RatingStrategy::Filter)]
+    #[test_case(RatingStrategy::Trie)]
+    fn test_calculate_life_support_rate(strategy: RatingStrategy) {
+        let report = example_report(strategy);
+        let expected = 230;
+        let actual = report.calculate_life_support_rate();
+        assert_eq!(actual, Ok(Some(expected)));
+    }
+
+    #[test_case(RatingStrategy::Filter)]
+    #[test_case(RatingStrategy::Trie)]
+    fn test_empty_life_support_report_is_rejected(strategy: RatingStrategy) {
+        let mut report = LifeSupportReport::with_strategy(strategy);
+        run(&mut "\n\n".as_bytes(), &mut report).unwrap();
+
+        assert_eq!(report.get_oxygen_gen_rate(), Err(ReadingError::EmptyReport));
+        assert_eq!(
+            report.get_co2_scrubber_rate(),
+            Err(ReadingError::EmptyReport)
+        );
+        assert_eq!(
+            report.calculate_life_support_rate(),
+            Err(ReadingError::EmptyReport)
+        );
+    }
+
+    #[test_case(1, 2)]
+    #[test_case(5, 12)]
+    #[test_case(12, 1000)]
+    #[test_case(40, 5000)]
+    #[test_case(128, 5000)]
+    fn test_trie_matches_filter(width: usize, lines: usize) {
+        let mut state: u128 = 0x2545_f491_4f6c_dd1d_9e37_79b9_7f4a_7c15;
+        let mut readings = vec![];
+        for _ in 0..lines {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            readings.push(state >> (128 - width));
+        }
+        let filter = LifeSupportReport {
+            width,
+            readings: readings.clone(),
+            strategy: RatingStrategy::Filter,
+            ..Default::default()
+        };
+        let trie = LifeSupportReport {
+            width,
+            readings,
+            strategy: RatingStrategy::Trie,
+            ..Default::default()
+        };
+
+        assert_eq!(filter.get_oxygen_gen_rate(), trie.get_oxygen_gen_rate());
+        assert_eq!(filter.get_co2_scrubber_rate(), trie.get_co2_scrubber_rate());
+    }
+
+    #[test]
+    fn test_wide_readings_are_not_truncated() {
+        let input = format!(
+            "{}\n{}\n{}\n",
+            "1".repeat(40),
+            "1".repeat(40),
+            "0".repeat(40)
+        );
+        let mut power = PowerReport::default();
+        let mut life_support = LifeSupportReport::default();
+
+        run(&mut input.as_bytes(), &mut power).unwrap();
+        run(&mut input.as_bytes(), &mut life_support).unwrap();
+        assert_eq!(power.get_gamma_rate(), (1 << 40) - 1);
+        assert_eq!(power.get_epsilon_rate(), 0);
+        assert_eq!(life_support.get_oxygen_gen_rate(), Ok((1 << 40) - 1));
+        assert_eq!(life_support.get_co2_scrubber_rate(), Ok(0));
+    }
+
+    #[test]
+    fn test_power_consumption_overflow_is_reported() {
+        let report = binary_report(2, [vec![2; 64], vec![0; 64]].concat());
+
+        assert_eq!(report.get_gamma_rate(), u128::MAX << 64);
+        assert_eq!(report.get_epsilon_rate(), u128::MAX >> 64);
+        assert_eq!(report.get_power_consumption(), None);
+    }
+
+    #[test]
+    fn test_run_fans_out_to_tuple_of_reports() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+        let mut power = PowerReport::default();
+        let mut life_support = LifeSupportReport::default();
+
+        run(&mut input.as_bytes(), &mut (&mut power, &mut life_support)).unwrap();
+        assert_eq!(power.get_power_consumption(), Some(198));
+        assert_eq!(life_support.calculate_life_support_rate(), Ok(Some(230)));
+    }
+
+    #[test]
+    fn test_run_fans_out_to_boxed_reports() {
+        let input =
+            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+        let mut power = PowerReport::default();
+        let mut filter = LifeSupportReport::with_strategy(RatingStrategy::Filter);
+        let mut trie = LifeSupportReport::with_strategy(RatingStrategy::Trie);
+        let mut reports: Vec<Box<dyn ReportParser + '_>> = vec![
+            Box::new(&mut power),
+            Box::new(&mut filter),
+            Box::new(&mut trie),
+        ];
+
+        run(&mut input.as_bytes(), &mut reports).unwrap();
+        drop(reports);
+        assert_eq!(power.total, 12);
+        assert_eq!(filter.calculate_life_support_rate(), Ok(Some(230)));
+        assert_eq!(trie.calculate_life_support_rate(), Ok(Some(230)));
+    }
+
+    #[test]
+    fn test_fan_out_rejecting_reading_leaves_every_report_untouched() {
+        let quaternary = Radix::new(4).unwrap();
+        let mut life_support =
+            LifeSupportReport::new(quaternary, TieRule::Standard, RatingStrategy::Filter);
+        let mut power = PowerReport::default();
+        let error = ReadingError::InvalidCharacter {
+            line: 1,
+            character: '2',
+        };
+
+        let mut reports = (&mut life_support, &mut power);
+        assert_eq!(reports.update_report(1, "12"), Err(error.clone()));
+        let mut reports: Vec<Box<dyn ReportParser + '_>> =
+            vec![Box::new(&mut life_support), Box::new(&mut power)];
+        assert_eq!(reports.update_report(1, "12"), Err(error));
+        drop(reports);
+        assert!(life_support.readings.is_empty());
+        assert_eq!(power.total, 0);
+    }
+
+    #[test_case(4, "0132\n3123\n2130\n3302\n" => (0b11_01_11_10, 0b01_00_01_01, 4))]
+    #[test_case(16, "f0\nf1\na1\n" => (0xf1, 0x02, 16))]
+    #[test_case(10, "19\n29\n28\n" => (29, 0, 10))]
+    fn test_power_report_with_radix(base: u32, input: &str) -> (u128, u128, usize) {
+        let radix = Radix::new(base).unwrap();
+        let mut report = PowerReport::new(radix, TieRule::Standard);
+
+        run(&mut input.as_bytes(), &mut report).unwrap();
+        (
+            report.get_gamma_rate(),
+            report.get_epsilon_rate(),
+            report.column_counts[0].len(),
+        )
+    }
+
+    #[test_case(TieRule::Standard => (0b11_11, 0b00_00))]
+    #[test_case(TieRule::Highest => (0b11_11, 0b11_11))]
+    #[test_case(TieRule::Lowest => (0b00_00, 0b00_00))]
+    fn test_power_report_tie_rule(tie_rule: TieRule) -> (u128, u128) {
+        let radix = Radix::new(4).unwrap();
+        let mut report = PowerReport::new(radix, tie_rule);
+
+        run(&mut "01\n10\n23\n32\n".as_bytes(), &mut report).unwrap();
+        (report.get_gamma_rate(), report.get_epsilon_rate())
+    }
+
+    #[test_case(RatingStrategy::Filter)]
+    #[test_case(RatingStrategy::Trie)]
+    fn test_life_support_report_with_radix(strategy: RatingStrategy) {
+        let radix = Radix::new(3).unwrap();
+        let input = "210\n201\n122\n211\n020\n";
+        let mut report = LifeSupportReport::new(radix, TieRule::Standard, strategy);
+
+        run(&mut input.as_bytes(), &mut report).unwrap();
+        assert_eq!(report.get_oxygen_gen_rate(), Ok(22));
+        assert_eq!(report.get_co2_scrubber_rate(), Ok(6));
+    }
+
+    #[test]
+    fn test_run_rejects_digits_outside_report_radix() {
+        let radix = Radix::new(4).unwrap();
+        let mut report = PowerReport::new(radix, TieRule::Standard);
+
+        let actual = run(&mut "0123\n0143\n".as_bytes(), &mut report);
+        assert_eq!(
+            actual,
+            Err(ReadingError::InvalidCharacter {
+                line: 2,
+                character: '4'
+            })
+        );
+    }
+}
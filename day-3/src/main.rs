@@ -1,309 +1,201 @@
+use day_3::{
+    bounded_life_support_ratings, rank_anomalies, run, run_with_options, Breakdown,
+    LifeSupportReport, MemoryMode, PowerReport, Radix, RatingStrategy, ReadingError, RunOptions,
+    Strictness, TieRule,
+};
+use std::env;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-struct PowerReport {
-    total: u32,
-    column_sums: Vec<u32>,
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::process;
+use verify::Part;
+
+fn task_one(report: &PowerReport) {
+    let power_consumption = report
+        .get_power_consumption()
+        .expect("Power consumption doesn't fit in 128 bits!");
+    println!("The power consumption is: {}", power_consumption);
 }
 
-trait ReportParser {
-    fn update_report(&mut self, reading: &str);
+fn task_two(oxygen_gen_rate: u128, co2_scrubber_rate: u128) {
+    let life_support_rate = oxygen_gen_rate
+        .checked_mul(co2_scrubber_rate)
+        .expect("Life support rate doesn't fit in 128 bits!");
+    println!("The Life Support is: {}", life_support_rate);
 }
 
-impl PowerReport {
-    fn get_gamma_rate(&self) -> String {
-        let mut gamma_rate = String::new();
-
-        for &value in self.column_sums.iter() {
-            if value >= (self.total as f32 / 2.0).ceil() as u32 {
-                gamma_rate.push_str("1");
-            } else {
-                gamma_rate.push_str("0");
-            }
-        }
-        gamma_rate
-    }
-
-    fn get_epsilon_rate(&self) -> String {
-        let mut epsilon_rate = String::new();
-
-        for &value in self.column_sums.iter() {
-            if value >= (self.total as f32 / 2.0).ceil() as u32 {
-                epsilon_rate.push_str("0");
-            } else {
-                epsilon_rate.push_str("1");
-            }
-        }
-        epsilon_rate
-    }
-
-    fn get_power_consumption(&self) -> u32 {
-        let gamma_rate = isize::from_str_radix(&self.get_gamma_rate(), 2).unwrap() as u32;
-        let epsilon_rate = isize::from_str_radix(&self.get_epsilon_rate(), 2).unwrap() as u32;
-
-        gamma_rate * epsilon_rate
+fn breakdown(power: &PowerReport, life_support: &LifeSupportReport, format: &str) {
+    let breakdown = Breakdown::build(power, life_support).expect("Cannot break down the report!");
+    match format {
+        "table" => println!("{}", breakdown),
+        "json" => println!("{}", breakdown.to_json()),
+        _ => panic!("Unknown breakdown format {}!", format),
     }
 }
 
-impl ReportParser for PowerReport {
-    fn update_report(&mut self, reading: &str) {
-        self.total += 1;
-        if self.column_sums.len() == 0 {
-            self.column_sums = vec![0; reading.len() - 1];
-        }
-
-        for (i, bite) in reading.as_bytes().iter().enumerate() {
-            match bite {
-                b'1' => self.column_sums[i] += 1,
-                _ => continue,
-            }
-        }
+fn anomalies<R: BufRead + Seek>(
+    readings: &mut R,
+    power: &PowerReport,
+    strictness: Strictness,
+    top: usize,
+) {
+    readings
+        .seek(SeekFrom::Start(0))
+        .expect("Cannot rewind readings!");
+    let anomalies =
+        rank_anomalies(readings, power, strictness, top).expect("Diagnostic report is corrupted!");
+    println!("The {} most anomalous readings:", anomalies.len());
+    for anomaly in anomalies {
+        println!(
+            "line {}: {} (score {:.3})",
+            anomaly.line, anomaly.reading, anomaly.score
+        );
     }
 }
 
-struct LifeSupportReport {
-    readings: Vec<String>,
-}
-
-impl LifeSupportReport {
-    fn get_oxygen_gen_rate(&mut self) -> u32 {
-        let mut readings: Vec<String> = self.readings.iter().cloned().collect();
-        for i in 0..readings[0].len() {
-            let mut bags = [vec![], vec![]];
-            for reading in &readings {
-                match reading.chars().nth(i).unwrap() {
-                    '1' => bags[1].push(reading.clone()),
-                    '0' => bags[0].push(reading.clone()),
-                    _ => continue,
-                }
-            }
-            readings = bags[(bags[0].len() <= bags[1].len()) as usize]
-                .iter()
-                .cloned()
-                .collect();
-            if readings.len() == 1 {
-                break;
-            }
-        }
-
-        isize::from_str_radix(&readings[0], 2).unwrap() as u32
-    }
-
-    fn get_co2_scrubber_rate(&mut self) -> u32 {
-        let mut readings: Vec<String> = self.readings.iter().cloned().collect();
-        for i in 0..readings[0].len() {
-            let mut bags = [vec![], vec![]];
-            for reading in &readings {
-                match reading.chars().nth(i).unwrap() {
-                    '1' => bags[1].push(reading.clone()),
-                    '0' => bags[0].push(reading.clone()),
-                    _ => continue,
-                }
-            }
-            readings = bags[!(bags[0].len() <= bags[1].len()) as usize]
-                .iter()
-                .cloned()
-                .collect();
-            if readings.len() == 1 {
-                break;
-            };
-        }
-        isize::from_str_radix(&readings[0], 2).unwrap() as u32
-    }
-
-    fn calculate_life_support_rate(&mut self) -> u32 {
-        self.get_oxygen_gen_rate() * self.get_co2_scrubber_rate()
+fn report_skipped(rejected: &[ReadingError]) {
+    for error in rejected {
+        eprintln!("Skipped {}", error);
     }
 }
 
-impl ReportParser for LifeSupportReport {
-    fn update_report(&mut self, reading: &str) {
-        self.readings.push(reading.trim().to_string());
-    }
+fn readings() -> BufReader<File> {
+    BufReader::new(File::open("src/input.txt").expect("Cannot open file!"))
 }
 
-fn run<R: BufRead, T: ReportParser>(readings: &mut R, report: &mut T) {
-    let mut first_line = String::new();
-    readings
-        .read_line(&mut first_line)
-        .expect("Cannot read line!");
-    report.update_report(&first_line);
-
-    for reading in readings.lines() {
-        let reading = reading.expect("Cannot read line!");
-        report.update_report(&reading);
-    }
+fn life_support(strategy: RatingStrategy) -> String {
+    let mut report = LifeSupportReport::new(Default::default(), TieRule::default(), strategy);
+    run(&mut readings(), &mut report).expect("Diagnostic report is corrupted!");
+    report
+        .calculate_life_support_rate()
+        .expect("Cannot rate life support!")
+        .expect("Life support rate doesn't fit in 128 bits!")
+        .to_string()
 }
 
-fn task_one() {
-    let file = File::open("src/input.txt").expect("Cannot open file!");
-    let mut readings = BufReader::new(file);
-    let mut report = PowerReport {
-        total: 0,
-        column_sums: vec![],
-    };
-
-    run(&mut readings, &mut report);
-    let power_consumption = report.get_power_consumption();
-    println!("The power consumption is: {}", power_consumption);
+/// Checks every way of solving both parts against `answers.toml`.
+fn verify_answers() -> bool {
+    let parts = [
+        Part {
+            name: "part_one",
+            variant: None,
+            solve: || {
+                let mut report = PowerReport::default();
+                run(&mut readings(), &mut report).expect("Diagnostic report is corrupted!");
+                report
+                    .get_power_consumption()
+                    .expect("Power consumption doesn't fit in 128 bits!")
+                    .to_string()
+            },
+        },
+        Part {
+            name: "part_two",
+            variant: Some("filter"),
+            solve: || life_support(RatingStrategy::Filter),
+        },
+        Part {
+            name: "part_two",
+            variant: Some("trie"),
+            solve: || life_support(RatingStrategy::Trie),
+        },
+        Part {
+            name: "part_two",
+            variant: Some("bounded"),
+            solve: || {
+                let (oxygen_gen_rate, co2_scrubber_rate) = bounded_life_support_ratings(
+                    &mut readings(),
+                    Radix::BINARY,
+                    RunOptions::default(),
+                    TieRule::default(),
+                )
+                .expect("Diagnostic report is corrupted!");
+                oxygen_gen_rate
+                    .checked_mul(co2_scrubber_rate)
+                    .expect("Life support rate doesn't fit in 128 bits!")
+                    .to_string()
+            },
+        },
+    ];
+    verify::run("answers.toml", &parts)
 }
 
-fn task_two() {
-    let file = File::open("src/input.txt").expect("Cannot open file!");
-    let mut readings = BufReader::new(file);
-    let mut report = LifeSupportReport { readings: vec![] };
-
-    run(&mut readings, &mut report);
-    let life_support_rate = report.calculate_life_support_rate();
-    println!("The Life Support is: {}", life_support_rate);
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .map(|i| args.get(i + 1).expect("Missing option value!").as_str())
 }
 
 fn main() {
-    task_one();
-    task_two();
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use test_case::test_case;
-
-    #[test_case(12, [7, 5, 7, 7, 4].to_vec(), r#"10110"#)]
-    #[test_case(4, [3, 0, 2, 4].to_vec(), r#"1011"#)]
-    #[test_case(5, [3, 1, 2, 4, 5].to_vec(), r#"10011"#)]
-    fn test_get_gamma_rate_for_report(total: u32, column_sums: Vec<u32>, expected: &str) {
-        let report = PowerReport {
-            total: total,
-            column_sums: column_sums,
-        };
-        let actual = report.get_gamma_rate();
-
-        assert_eq!(actual, expected);
-    }
-
-    #[test_case(12, [7, 5, 7, 7, 4].to_vec(), r#"01001"#)]
-    #[test_case(4, [3, 0, 2, 4].to_vec(), r#"0100"#)]
-    #[test_case(5, [3, 1, 2, 4, 5].to_vec(), r#"01100"#)]
-    fn test_get_epsilon_rate_for_report(total: u32, column_sums: Vec<u32>, expected: &str) {
-        let report = PowerReport {
-            total: total,
-            column_sums: column_sums,
-        };
-        let actual = report.get_epsilon_rate();
-
-        assert_eq!(actual, expected);
-    }
-
-    #[test_case(12, [7, 5, 7, 7, 4].to_vec(), 22*9)]
-    #[test_case(4, [3, 0, 2, 4].to_vec(), 11*4)]
-    #[test_case(5, [3, 1, 2, 4, 5].to_vec(), 19*12)]
-    fn test_get_power_consumption_for_report(total: u32, column_sums: Vec<u32>, expected: u32) {
-        let report = PowerReport {
-            total: total,
-            column_sums: column_sums,
-        };
-        let actual = report.get_power_consumption();
-
-        assert_eq!(actual, expected);
-    }
-
-    #[test_case([12, 4, 0, 2, 11, 0, 9, 8, 8, 0].to_vec(), "0110100111", [12, 5, 1, 2, 12, 0, 9, 9, 9, 1].to_vec())]
-    fn test_update_report(initial_state: Vec<u32>, reading: &str, expected: Vec<u32>) {
-        let mut actual = PowerReport {
-            total: 2,
-            column_sums: initial_state,
-        };
-        actual.update_report(reading);
-
-        assert_eq!(actual.total, 3, "The total value is not equal");
-        for (actual_bit, expected_bit) in actual.column_sums.iter().zip(expected.iter()) {
-            assert_eq!(actual_bit, expected_bit, "The column sum doesn't match");
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("verify") {
+        if !verify_answers() {
+            process::exit(1);
         }
+        return;
     }
+    let strategy = match option_value(&args, "--strategy") {
+        Some(strategy) => strategy.parse().expect("Cannot parse rating strategy!"),
+        None => RatingStrategy::default(),
+    };
+    let mode = match option_value(&args, "--memory") {
+        Some(mode) => mode.parse().expect("Cannot parse memory mode!"),
+        None => MemoryMode::default(),
+    };
+    let radix = match option_value(&args, "--radix") {
+        Some(radix) => radix.parse().expect("Cannot parse radix!"),
+        None => Default::default(),
+    };
+    let strictness = match option_value(&args, "--strictness") {
+        Some(strictness) => strictness.parse().expect("Cannot parse strictness!"),
+        None => Strictness::default(),
+    };
+    let options = RunOptions { strictness };
+    let tie_rule = match option_value(&args, "--ties") {
+        Some(tie_rule) => tie_rule.parse().expect("Cannot parse tie rule!"),
+        None => TieRule::default(),
+    };
+    let breakdown_format = option_value(&args, "--breakdown");
 
-    #[test]
-    fn test_run() {
-        let input =
-            "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
-        let mut report = PowerReport {
-            total: 0,
-            column_sums: vec![],
-        };
-
-        run(&mut input.as_bytes(), &mut report);
-        let actual = report.get_power_consumption();
-        println!("{} {}", report.get_gamma_rate(), report.get_epsilon_rate());
-        assert_eq!(actual, 198);
-    }
-
-    #[test]
-    fn test_get_oxygen_gen_rate() {
-        let mut report = LifeSupportReport {
-            readings: vec![
-                "00100".to_string(),
-                "11110".to_string(),
-                "10110".to_string(),
-                "10111".to_string(),
-                "10101".to_string(),
-                "01111".to_string(),
-                "00111".to_string(),
-                "11100".to_string(),
-                "10000".to_string(),
-                "11001".to_string(),
-                "00010".to_string(),
-                "01010".to_string(),
-            ],
-        };
-        let expected = 23;
-        let actual = report.get_oxygen_gen_rate();
-
-        assert_eq!(actual, expected);
-    }
-
-    #[test]
-    fn test_get_co2_scrubber_rate() {
-        let mut report = LifeSupportReport {
-            readings: vec![
-                "00100".to_string(),
-                "11110".to_string(),
-                "10110".to_string(),
-                "10111".to_string(),
-                "10101".to_string(),
-                "01111".to_string(),
-                "00111".to_string(),
-                "11100".to_string(),
-                "10000".to_string(),
-                "11001".to_string(),
-                "00010".to_string(),
-                "01010".to_string(),
-            ],
-        };
-        let expected = 10;
-        let actual = report.get_co2_scrubber_rate();
-
-        assert_eq!(actual, expected);
+    let file = File::open("src/input.txt").expect("Cannot open file!");
+    let mut readings = BufReader::new(file);
+    let mut power = PowerReport::new(radix, tie_rule);
+    match mode {
+        MemoryMode::InMemory => {
+            let mut life_support = LifeSupportReport::new(radix, tie_rule, strategy);
+            let rejected =
+                run_with_options(&mut readings, options, &mut (&mut power, &mut life_support))
+                    .expect("Diagnostic report is corrupted!");
+            report_skipped(&rejected);
+
+            task_one(&power);
+            task_two(
+                life_support
+                    .get_oxygen_gen_rate()
+                    .expect("Cannot rate oxygen generator!"),
+                life_support
+                    .get_co2_scrubber_rate()
+                    .expect("Cannot rate CO2 scrubber!"),
+            );
+            if let Some(format) = breakdown_format {
+                breakdown(&power, &life_support, format);
+            }
+        }
+        MemoryMode::Bounded => {
+            if breakdown_format.is_some() {
+                panic!("The breakdown needs the readings in memory!");
+            }
+            let rejected = run_with_options(&mut readings, options, &mut power)
+                .expect("Diagnostic report is corrupted!");
+            report_skipped(&rejected);
+            let (oxygen_gen_rate, co2_scrubber_rate) =
+                bounded_life_support_ratings(&mut readings, radix, options, tie_rule)
+                    .expect("Diagnostic report is corrupted!");
+
+            task_one(&power);
+            task_two(oxygen_gen_rate, co2_scrubber_rate);
+        }
     }
-
-    #[test]
-    fn test_calculate_life_support_rate() {
-        let mut report = LifeSupportReport {
-            readings: vec![
-                "00100".to_string(),
-                "11110".to_string(),
-                "10110".to_string(),
-                "10111".to_string(),
-                "10101".to_string(),
-                "01111".to_string(),
-                "00111".to_string(),
-                "11100".to_string(),
-                "10000".to_string(),
-                "11001".to_string(),
-                "00010".to_string(),
-                "01010".to_string(),
-            ],
-        };
-        let expected = 230;
-        let actual = report.calculate_life_support_rate();
-        assert_eq!(actual, expected);
+    if let Some(top) = option_value(&args, "--anomalies") {
+        let top = top.parse().expect("Cannot parse number of anomalies!");
+        anomalies(&mut readings, &power, strictness, top);
     }
 }
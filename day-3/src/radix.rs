@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+/// Number of bits available to a packed reading.
+const PACKED_BITS: u32 = 128;
+
+/// Base of the diagnostic codes. Readings are packed into a `u128` using the fewest
+/// bits that can hold one digit, so binary readings stay plain bit patterns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Radix {
+    base: u32,
+    bits: u32,
+}
+
+impl Default for Radix {
+    fn default() -> Self {
+        Radix::BINARY
+    }
+}
+
+impl Radix {
+    pub const BINARY: Radix = Radix { base: 2, bits: 1 };
+
+    pub fn new(base: u32) -> Result<Radix, String> {
+        if !(2..=36).contains(&base) {
+            return Err(format!("Radix {} is not between 2 and 36!", base));
+        }
+
+        Ok(Radix {
+            base,
+            bits: u32::BITS - (base - 1).leading_zeros(),
+        })
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Widest reading whose packed digits still fit in 128 bits.
+    pub fn max_width(&self) -> usize {
+        (PACKED_BITS / self.bits) as usize
+    }
+
+    pub fn is_digit(&self, character: char) -> bool {
+        character.is_digit(self.base)
+    }
+
+    pub(crate) fn pack(&self, reading: &str) -> u128 {
+        reading.chars().fold(0, |packed, character| {
+            let digit = character
+                .to_digit(self.base)
+                .expect("Cannot parse reading!");
+            (packed << self.bits) | digit as u128
+        })
+    }
+
+    /// Returns the digit in `column`, counting from the most significant one.
+    pub(crate) fn digit(&self, packed: u128, width: usize, column: usize) -> usize {
+        let shift = (width - 1 - column) as u32 * self.bits;
+        ((packed >> shift) & ((1 << self.bits) - 1)) as usize
+    }
+
+    /// Converts packed digits into the number they spell in this radix.
+    pub(crate) fn value(&self, packed: u128, width: usize) -> u128 {
+        self.value_of_digits((0..width).map(|column| self.digit(packed, width, column)))
+    }
+
+    pub(crate) fn value_of_digits<I: Iterator<Item = usize>>(&self, digits: I) -> u128 {
+        digits.fold(0, |value, digit| value * self.base as u128 + digit as u128)
+    }
+}
+
+impl FromStr for Radix {
+    type Err = String;
+
+    fn from_str(base: &str) -> Result<Self, Self::Err> {
+        let base = base
+            .parse()
+            .map_err(|_| format!("Unknown radix {}!", base))?;
+        Radix::new(base)
+    }
+}
+
+/// Decides which digit wins when several are equally common.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TieRule {
+    /// The most common tie goes to the highest digit and the least common tie to the
+    /// lowest one, as the submarine manual does for binary readings.
+    #[default]
+    Standard,
+    Highest,
+    Lowest,
+}
+
+impl TieRule {
+    fn prefers_highest(&self, most_common: bool) -> bool {
+        match self {
+            TieRule::Standard => most_common,
+            TieRule::Highest => true,
+            TieRule::Lowest => false,
+        }
+    }
+
+    /// Picks the most or least common digit from `(digit, count)` pairs given in
+    /// ascending digit order.
+    pub(crate) fn select<I: Iterator<Item = (usize, usize)>>(
+        &self,
+        counts: I,
+        most_common: bool,
+    ) -> usize {
+        let prefers_highest = self.prefers_highest(most_common);
+        let mut selected: Option<(usize, usize)> = None;
+        for (digit, count) in counts {
+            let better = match selected {
+                None => true,
+                Some((_, selected_count)) if count == selected_count => prefers_highest,
+                Some((_, selected_count)) => (count > selected_count) == most_common,
+            };
+            if better {
+                selected = Some((digit, count));
+            }
+        }
+
+        selected.expect("Cannot select a digit without counts!").0
+    }
+}
+
+impl FromStr for TieRule {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "standard" => Ok(TieRule::Standard),
+            "highest" => Ok(TieRule::Highest),
+            "lowest" => Ok(TieRule::Lowest),
+            _ => Err(format!("Unknown tie rule {}!", name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(2 => (1, 128))]
+    #[test_case(3 => (2, 64))]
+    #[test_case(4 => (2, 64))]
+    #[test_case(10 => (4, 32))]
+    #[test_case(16 => (4, 32))]
+    #[test_case(36 => (6, 21))]
+    fn new_picks_bits_per_digit(base: u32) -> (u32, usize) {
+        let radix = Radix::new(base).unwrap();
+        (radix.bits, radix.max_width())
+    }
+
+    #[test_case("1")]
+    #[test_case("37")]
+    #[test_case("hex")]
+    fn from_str_rejects_unsupported_radix(base: &str) {
+        assert!(base.parse::<Radix>().is_err());
+    }
+
+    #[test_case(4, "3120" => 0b11_01_10_00)]
+    #[test_case(16, "f0a" => 0xf0a)]
+    #[test_case(10, "95" => 0b1001_0101)]
+    fn pack_stores_digits_side_by_side(base: u32, reading: &str) -> u128 {
+        Radix::new(base).unwrap().pack(reading)
+    }
+
+    #[test_case(4, "3120" => 216)]
+    #[test_case(16, "f0a" => 0xf0a)]
+    #[test_case(10, "95" => 95)]
+    fn value_reads_number_in_radix(base: u32, reading: &str) -> u128 {
+        let radix = Radix::new(base).unwrap();
+        radix.value(radix.pack(reading), reading.len())
+    }
+
+    #[test_case(TieRule::Standard, true => 2)]
+    #[test_case(TieRule::Standard, false => 0)]
+    #[test_case(TieRule::Highest, false => 3)]
+    #[test_case(TieRule::Lowest, true => 1)]
+    fn select_breaks_ties(tie_rule: TieRule, most_common: bool) -> usize {
+        let counts = [(0, 1), (1, 4), (2, 4), (3, 1)];
+        tie_rule.select(counts.into_iter(), most_common)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{Radix, TieRule};
+
+/// Trie over packed readings, most significant digit first. Every node keeps the
+/// number of readings passing through it, so the rating criteria only need to compare
+/// the counters of its children.
+pub struct ReadingTrie {
+    radix: Radix,
+    width: usize,
+    nodes: Vec<Node>,
+}
+
+struct Node {
+    count: u32,
+    children: Vec<Option<usize>>,
+}
+
+impl Node {
+    fn new(radix: Radix) -> Node {
+        Node {
+            count: 0,
+            children: vec![None; radix.base() as usize],
+        }
+    }
+}
+
+impl ReadingTrie {
+    pub fn build(radix: Radix, width: usize, readings: &[u128]) -> ReadingTrie {
+        let mut nodes = vec![Node::new(radix)];
+        for &reading in readings {
+            let mut current = 0;
+            nodes[current].count += 1;
+            for column in 0..width {
+                let digit = radix.digit(reading, width, column);
+                let next = match nodes[current].children[digit] {
+                    Some(next) => next,
+                    None => {
+                        nodes.push(Node::new(radix));
+                        nodes[current].children[digit] = Some(nodes.len() - 1);
+                        nodes.len() - 1
+                    }
+                };
+                nodes[next].count += 1;
+                current = next;
+            }
+        }
+
+        ReadingTrie {
+            radix,
+            width,
+            nodes,
+        }
+    }
+
+    fn walk(&self, tie_rule: TieRule, most_common: bool) -> u128 {
+        let mut current = 0;
+        let mut digits = Vec::with_capacity(self.width);
+        for _ in 0..self.width {
+            let children = &self.nodes[current].children;
+            let present = children
+                .iter()
+                .enumerate()
+                .filter_map(|(digit, child)| child.map(|child| (digit, child)))
+                .map(|(digit, child)| (digit, self.nodes[child].count as usize));
+            let digit = tie_rule.select(present, most_common);
+            digits.push(digit);
+            current = children[digit].unwrap();
+        }
+
+        self.radix.value_of_digits(digits.into_iter())
+    }
+
+    pub fn get_oxygen_gen_rate(&self, tie_rule: TieRule) -> u128 {
+        self.walk(tie_rule, true)
+    }
+
+    pub fn get_co2_scrubber_rate(&self, tie_rule: TieRule) -> u128 {
+        self.walk(tie_rule, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(trie: &ReadingTrie, node: Option<usize>) -> u32 {
+        node.map_or(0, |node| trie.nodes[node].count)
+    }
+
+    #[test]
+    fn build_counts_readings_per_node() {
+        let trie = ReadingTrie::build(Radix::BINARY, 2, &[0b10, 0b11, 0b11]);
+
+        assert_eq!(trie.nodes[0].count, 3);
+        assert_eq!(count(&trie, trie.nodes[0].children[0]), 0);
+        let ones = trie.nodes[0].children[1].unwrap();
+        assert_eq!(trie.nodes[ones].count, 3);
+        assert_eq!(count(&trie, trie.nodes[ones].children[0]), 1);
+        assert_eq!(count(&trie, trie.nodes[ones].children[1]), 2);
+    }
+
+    #[test]
+    fn walk_follows_the_only_branch_left() {
+        let trie = ReadingTrie::build(Radix::BINARY, 3, &[0b100, 0b101, 0b111]);
+
+        assert_eq!(trie.get_oxygen_gen_rate(TieRule::Standard), 0b101);
+        assert_eq!(trie.get_co2_scrubber_rate(TieRule::Standard), 0b111);
+    }
+
+    #[test]
+    fn walk_picks_digits_in_radix() {
+        let radix = Radix::new(10).unwrap();
+        let readings: Vec<u128> = ["31", "35", "38", "72"]
+            .iter()
+            .map(|reading| radix.pack(reading))
+            .collect();
+        let trie = ReadingTrie::build(radix, 2, &readings);
+
+        assert_eq!(trie.get_oxygen_gen_rate(TieRule::Standard), 38);
+        assert_eq!(trie.get_co2_scrubber_rate(TieRule::Standard), 72);
+        assert_eq!(trie.get_oxygen_gen_rate(TieRule::Lowest), 31);
+    }
+}
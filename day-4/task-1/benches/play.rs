@@ -0,0 +1,99 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use input::testing::xorshift;
+use task_1::{build_gameboards, play, rank_boards, WinRule};
+
+const BOARDS: usize = 5_000;
+const SIZE: usize = 5;
+const NUMBERS: u64 = 1_000;
+
+/// Shuffles `0..NUMBERS` and returns the first `count` numbers.
+fn shuffled(state: &mut u64, count: usize) -> Vec<u32> {
+    let mut numbers: Vec<u32> = (0..NUMBERS as u32).collect();
+    for i in (1..numbers.len()).rev() {
+        numbers.swap(i, (xorshift(state) % (i as u64 + 1)) as usize);
+    }
+    numbers.truncate(count);
+    numbers
+}
+
+fn generate_input() -> (Vec<u32>, String) {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let lucky_numbers = shuffled(&mut state, NUMBERS as usize);
+    let mut boards = String::new();
+    for _ in 0..BOARDS {
+        for row in shuffled(&mut state, SIZE * SIZE).chunks(SIZE) {
+            let row: Vec<String> = row.iter().map(|number| format!("{:3}", number)).collect();
+            boards.push_str(&row.join(" "));
+            boards.push('\n');
+        }
+        boards.push('\n');
+    }
+    (lucky_numbers, boards)
+}
+
+/// Plays the way day-4 did before boards indexed their numbers: each draw searches every
+/// cell of every board, then rechecks the whole row and column of a hit. "scan" times
+/// this against `play` ("indexed") and `rank_boards` ("ranked") on the same 5,000
+/// boards. Returns the score of the last board to win.
+fn scan(lucky_numbers: &[u32], boards: &[Vec<Vec<u32>>]) -> u32 {
+    let mut marked = vec![vec![vec![false; SIZE]; SIZE]; boards.len()];
+    let mut has_won = vec![false; boards.len()];
+    let mut last_score = 0;
+    for &lucky_number in lucky_numbers {
+        for (i, board) in boards.iter().enumerate() {
+            if has_won[i] {
+                continue;
+            }
+            for x in 0..SIZE {
+                for y in 0..SIZE {
+                    if board[x][y] == lucky_number {
+                        marked[i][x][y] = true;
+                        let row = (0..SIZE).all(|column| marked[i][x][column]);
+                        let column = (0..SIZE).all(|row| marked[i][row][y]);
+                        if row || column {
+                            has_won[i] = true;
+                            let unmarked: u32 = (0..SIZE * SIZE)
+                                .filter(|&cell| !marked[i][cell / SIZE][cell % SIZE])
+                                .map(|cell| board[cell / SIZE][cell % SIZE])
+                                .sum();
+                            last_score = unmarked * lucky_number;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    last_score
+}
+
+fn play_benchmark(c: &mut Criterion) {
+    let (lucky_numbers, input) = generate_input();
+    let boards: Vec<Vec<Vec<u32>>> = input
+        .split("\n\n")
+        .filter(|board| !board.is_empty())
+        .map(|board| {
+            board
+                .lines()
+                .map(|row| row.split_whitespace().map(|x| x.parse().unwrap()).collect())
+                .collect()
+        })
+        .collect();
+    let gameboards = build_gameboards(&mut input.as_bytes());
+    assert_eq!(gameboards.len(), BOARDS);
+    let wins = play(&lucky_numbers, gameboards.clone(), &WinRule::Lines);
+    assert_eq!(wins.last().map(|win| win.score), Some(scan(&lucky_numbers, &boards)));
+
+    let mut group = c.benchmark_group("play_5000_boards");
+    group.sample_size(10);
+    group.bench_function("scan", |b| b.iter(|| scan(&lucky_numbers, &boards)));
+    group.bench_function("indexed", |b| {
+        b.iter(|| play(&lucky_numbers, gameboards.clone(), &WinRule::Lines))
+    });
+    group.bench_function("ranked", |b| {
+        b.iter(|| rank_boards(&lucky_numbers, &gameboards, &WinRule::Lines))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, play_benchmark);
+criterion_main!(benches);
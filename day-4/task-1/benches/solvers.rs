@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use task_1::{
+    find_winning_board, generate_game, parse_game, BoardOptions, BoardSize, Gameboard,
+    GeneratorOptions, WinRule,
+};
+
+/// Input sizes relative to the bundled `src/input.txt`, in boards. Parsed boards take
+/// about 1.4 KB each, so 10,000x needs about 1.5 GB.
+const SCALES: [usize; 3] = [1, 100, 10_000];
+
+fn parse(input: &str) -> (Vec<u32>, Vec<Gameboard>) {
+    parse_game(&mut input.as_bytes(), &BoardOptions::default()).unwrap()
+}
+
+/// Solves boards parsed from the generated input for every batch, so parsing stays out
+/// of the timings and only the boards being solved are held in memory, not a copy kept
+/// around to clone from.
+fn solve((lucky_numbers, gameboards): (Vec<u32>, Vec<Gameboard>)) -> u32 {
+    let (lucky_number, gameboard) =
+        find_winning_board(lucky_numbers, gameboards, &WinRule::Lines).unwrap();
+    gameboard.sum_unmarked() * lucky_number
+}
+
+fn solvers_benchmark(c: &mut Criterion) {
+    let (_, bundled) = parse(include_str!("../src/input.txt"));
+    let mut group = c.benchmark_group("day_4");
+    group.sample_size(10);
+    for scale in SCALES {
+        let boards = bundled.len() * scale;
+        let options = GeneratorOptions {
+            boards,
+            size: BoardSize { rows: 5, columns: 5 },
+            range: 100,
+            seed: 0,
+        };
+        let input = generate_game(&options).unwrap().to_string();
+        group.throughput(Throughput::Elements(boards as u64));
+        group.bench_with_input(
+            BenchmarkId::new("find_winning_board", format!("{}x", scale)),
+            &input,
+            |b, input| b.iter_batched(|| parse(input), solve, BatchSize::LargeInput),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solvers_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+use crate::protocol::{ClientMessage, Connection, ServerMessage};
+use crate::{Gameboard, Win, WinRule};
+use std::collections::HashSet;
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+
+fn unexpected(message: &ServerMessage) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Unexpected message {}!", message),
+    )
+}
+
+/// A player connected to a bingo `Server`.
+pub struct Client {
+    connection: Connection,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(address: A) -> io::Result<Client> {
+        Ok(Client {
+            connection: Connection::new(TcpStream::connect(address)?)?,
+        })
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        self.connection.send(message)
+    }
+
+    /// Reads the next message. A line the client cannot parse is an `InvalidData` error.
+    pub fn receive(&mut self) -> io::Result<ServerMessage> {
+        self.connection
+            .receive()?
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// Sends a message that gets an answer and waits for it.
+    pub fn request(&mut self, message: &ClientMessage) -> io::Result<ServerMessage> {
+        self.send(message)?;
+        self.receive()
+    }
+
+    /// Registers a board and returns its index. A rejected board is an `InvalidInput`
+    /// error carrying the server's message.
+    pub fn register(&mut self, board: &[Vec<u32>]) -> io::Result<usize> {
+        match self.request(&ClientMessage::Board(board.to_vec()))? {
+            ServerMessage::Registered(board_index) => Ok(board_index),
+            ServerMessage::Error(error) => Err(io::Error::new(io::ErrorKind::InvalidInput, error)),
+            message => Err(unexpected(&message)),
+        }
+    }
+
+    /// Scripted player: registers `boards`, marks its own copies as numbers are drawn
+    /// and claims every board on the draw that completes it. Returns all the wins the
+    /// server announced, its own and everyone else's.
+    pub fn play(mut self, boards: &[Vec<Vec<u32>>], rule: &WinRule) -> io::Result<Vec<Win>> {
+        let mut gameboards = vec![];
+        for board in boards {
+            let board_index = self.register(board)?;
+            gameboards.push((board_index, Gameboard::build(&mut board.clone())));
+        }
+        self.send(&ClientMessage::Ready)?;
+
+        let mut has_won = HashSet::new();
+        let mut wins = vec![];
+        loop {
+            match self.receive()? {
+                ServerMessage::Draw { number, .. } => {
+                    for (board_index, gameboard) in &mut gameboards {
+                        if has_won.contains(board_index) || gameboard.mark(number, rule).is_none() {
+                            continue;
+                        }
+                        has_won.insert(*board_index);
+                        match self.request(&ClientMessage::Claim(*board_index))? {
+                            ServerMessage::Claimed(_) => {}
+                            message => return Err(unexpected(&message)),
+                        }
+                    }
+                    self.send(&ClientMessage::Next)?;
+                }
+                ServerMessage::Win(win) => wins.push(win),
+                ServerMessage::End => return Ok(wins),
+                message => return Err(unexpected(&message)),
+            }
+        }
+    }
+}
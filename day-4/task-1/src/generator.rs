@@ -0,0 +1,375 @@
+use crate::{BoardSize, Gameboard, WinRule};
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+use rand::SeedableRng;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorOptions {
+    pub boards: usize,
+    pub size: BoardSize,
+    /// Numbers are drawn from `0..range`.
+    pub range: u32,
+    pub seed: u64,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            boards: 100,
+            size: BoardSize {
+                rows: 5,
+                columns: 5,
+            },
+            range: 100,
+            seed: 0,
+        }
+    }
+}
+
+/// Random draw order and boards, written out in the same format as `src/input.txt`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedGame {
+    pub lucky_numbers: Vec<u32>,
+    pub boards: Vec<Vec<Vec<u32>>>,
+}
+
+impl GeneratedGame {
+    pub fn gameboards(&self) -> Vec<Gameboard> {
+        self.boards
+            .iter()
+            .map(|board| Gameboard::build(&mut board.clone()))
+            .collect()
+    }
+}
+
+impl fmt::Display for GeneratedGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let draws: Vec<String> = self.lucky_numbers.iter().map(u32::to_string).collect();
+        let width = self
+            .lucky_numbers
+            .iter()
+            .max()
+            .map_or(1, |max| max.to_string().len());
+        writeln!(f, "{}", draws.join(","))?;
+        for board in &self.boards {
+            writeln!(f)?;
+            for row in board {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|number| format!("{:>width$}", number, width = width))
+                    .collect();
+                writeln!(f, "{}", cells.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn random_boards(rng: &mut StdRng, options: &GeneratorOptions) -> Vec<Vec<Vec<u32>>> {
+    let cells = options.size.rows * options.size.columns;
+    (0..options.boards)
+        .map(|_| {
+            let numbers: Vec<u32> = index::sample(rng, options.range as usize, cells)
+                .into_iter()
+                .map(|number| number as u32)
+                .collect();
+            numbers
+                .chunks(options.size.columns)
+                .map(|row| row.to_vec())
+                .collect()
+        })
+        .collect()
+}
+
+fn random_draws(rng: &mut StdRng, range: u32) -> Vec<u32> {
+    let mut lucky_numbers: Vec<u32> = (0..range).collect();
+    lucky_numbers.shuffle(rng);
+    lucky_numbers
+}
+
+fn validate(options: &GeneratorOptions) -> Result<(), String> {
+    if options.size.rows == 0 || options.size.columns == 0 {
+        return Err(format!("Board size {} has no cells!", options.size));
+    }
+    let cells = options.size.rows.saturating_mul(options.size.columns);
+    if (options.range as usize) < cells {
+        return Err(format!(
+            "Range {} is too small for a {} board!",
+            options.range, options.size
+        ));
+    }
+    // A score is at most every other number on the board left unmarked, times the
+    // number drawn last.
+    let highest = u64::from(options.range - 1);
+    let highest_score = (cells as u64)
+        .saturating_mul(highest)
+        .saturating_mul(highest);
+    if highest_score > u64::from(u32::MAX) {
+        return Err(format!(
+            "Range {} is too large for a {} board, scores wouldn't fit in 32 bits!",
+            options.range, options.size
+        ));
+    }
+    Ok(())
+}
+
+/// Generates boards holding distinct numbers and a draw order covering the whole range.
+/// The same options always give the same game.
+pub fn generate_game(options: &GeneratorOptions) -> Result<GeneratedGame, String> {
+    validate(options)?;
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let boards = random_boards(&mut rng, options);
+    let lucky_numbers = random_draws(&mut rng, options.range);
+
+    Ok(GeneratedGame {
+        lucky_numbers,
+        boards,
+    })
+}
+
+/// Outcome of replaying the same boards with many random draw orders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simulation {
+    pub games: usize,
+    /// Number of games by the number of draws it took for the first board to win.
+    pub draws_to_first_win: BTreeMap<usize, usize>,
+    /// Share of games each board won first. Boards tied for first all count as winners.
+    pub win_probabilities: Vec<f64>,
+}
+
+impl Simulation {
+    pub fn mean_draws_to_first_win(&self) -> f64 {
+        let total: usize = self
+            .draws_to_first_win
+            .iter()
+            .map(|(draws, games)| draws * games)
+            .sum();
+        total as f64 / self.games as f64
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Draws to the first win over {} games:", self.games)?;
+        for (draws, games) in &self.draws_to_first_win {
+            let bar = "#".repeat(games * 50 / self.games);
+            writeln!(f, "{}", format!("{:>4}: {:>6} {}", draws, games, bar).trim_end())?;
+        }
+        writeln!(f, "mean: {:.2}", self.mean_draws_to_first_win())?;
+        writeln!(f, "Probability of winning first:")?;
+        for (board, probability) in self.win_probabilities.iter().enumerate() {
+            writeln!(f, "board {:>3}: {:.3}", board, probability)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays until the first draw a board wins on and returns that draw's index with every
+/// board winning on it.
+fn first_winners(
+    lucky_numbers: &[u32],
+    mut gameboards: Vec<Gameboard>,
+    rule: &WinRule,
+) -> Option<(usize, Vec<usize>)> {
+    for (draw_index, &lucky_number) in lucky_numbers.iter().enumerate() {
+        let winners: Vec<usize> = gameboards
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(board_index, gameboard)| {
+                gameboard.mark(lucky_number, rule).map(|_| board_index)
+            })
+            .collect();
+        if !winners.is_empty() {
+            return Some((draw_index, winners));
+        }
+    }
+    None
+}
+
+/// Generates the boards once from `options`, then plays `games` games on them, each
+/// with a fresh random draw order. A game stops as soon as a board wins.
+pub fn simulate(
+    options: &GeneratorOptions,
+    games: usize,
+    rule: &WinRule,
+) -> Result<Simulation, String> {
+    if games == 0 {
+        return Err("Cannot simulate 0 games!".to_string());
+    }
+    rule.check_size(options.size)?;
+    let game = generate_game(options)?;
+    let gameboards = game.gameboards();
+    let mut rng = StdRng::seed_from_u64(options.seed.wrapping_add(1));
+    let mut draws_to_first_win = BTreeMap::new();
+    let mut first_wins = vec![0; gameboards.len()];
+    for _ in 0..games {
+        let lucky_numbers = random_draws(&mut rng, options.range);
+        let winners = first_winners(&lucky_numbers, gameboards.clone(), rule);
+        if let Some((draw_index, winners)) = winners {
+            *draws_to_first_win.entry(draw_index + 1).or_insert(0) += 1;
+            for board_index in winners {
+                first_wins[board_index] += 1;
+            }
+        }
+    }
+
+    Ok(Simulation {
+        games,
+        draws_to_first_win,
+        win_probabilities: first_wins
+            .into_iter()
+            .map(|wins| wins as f64 / games as f64)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_game, play, BoardOptions};
+    use std::collections::HashSet;
+
+    fn options(seed: u64) -> GeneratorOptions {
+        GeneratorOptions {
+            boards: 20,
+            size: BoardSize {
+                rows: 3,
+                columns: 4,
+            },
+            range: 40,
+            seed,
+        }
+    }
+
+    #[test]
+    fn generate_game_is_seeded() {
+        assert_eq!(generate_game(&options(7)), generate_game(&options(7)));
+        assert_ne!(generate_game(&options(7)), generate_game(&options(8)));
+    }
+
+    #[test]
+    fn generate_game_returns_boards_of_distinct_numbers_in_range() {
+        let game = generate_game(&options(1)).unwrap();
+
+        assert_eq!(game.boards.len(), 20);
+        for board in &game.boards {
+            assert_eq!(board.len(), 3);
+            let numbers: HashSet<u32> = board.iter().flatten().copied().collect();
+            assert_eq!(numbers.len(), 12);
+            assert!(numbers.iter().all(|&number| number < 40));
+        }
+        let mut draws = game.lucky_numbers.clone();
+        draws.sort_unstable();
+        assert_eq!(draws, (0..40).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn generated_game_display_round_trips_through_parser() {
+        let game = generate_game(&options(3)).unwrap();
+        let board_options = BoardOptions {
+            size: Some(options(3).size),
+            ..Default::default()
+        };
+
+        let (lucky_numbers, gameboards) =
+            parse_game(&mut game.to_string().as_bytes(), &board_options).unwrap();
+        assert_eq!(lucky_numbers, game.lucky_numbers);
+        assert_eq!(gameboards, game.gameboards());
+    }
+
+    #[test]
+    fn generate_game_rejects_range_smaller_than_board() {
+        let options = GeneratorOptions {
+            range: 11,
+            ..options(0)
+        };
+
+        assert_eq!(
+            generate_game(&options),
+            Err("Range 11 is too small for a 3x4 board!".to_string())
+        );
+    }
+
+    #[test]
+    fn generate_game_rejects_board_without_cells() {
+        let options = GeneratorOptions {
+            size: BoardSize {
+                rows: 3,
+                columns: 0,
+            },
+            ..options(0)
+        };
+
+        assert_eq!(
+            generate_game(&options),
+            Err("Board size 3x0 has no cells!".to_string())
+        );
+        assert_eq!(
+            simulate(&options, 10, &WinRule::Lines).unwrap_err(),
+            "Board size 3x0 has no cells!"
+        );
+    }
+
+    #[test]
+    fn generate_game_rejects_range_whose_scores_overflow() {
+        let options = GeneratorOptions {
+            range: 20_000,
+            ..options(0)
+        };
+
+        let expected = "Range 20000 is too large for a 3x4 board, scores wouldn't fit in 32 bits!";
+        assert_eq!(generate_game(&options), Err(expected.to_string()));
+        assert!(generate_game(&GeneratorOptions {
+            range: 18_000,
+            ..options
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn simulate_returns_distribution_over_games() {
+        let simulation = simulate(&options(5), 200, &WinRule::Lines).unwrap();
+
+        assert_eq!(simulation.draws_to_first_win.values().sum::<usize>(), 200);
+        assert!(simulation.draws_to_first_win.keys().all(|&draws| draws >= 3));
+        let probabilities: f64 = simulation.win_probabilities.iter().sum();
+        assert!(probabilities >= 1.0);
+        assert_eq!(simulation.win_probabilities.len(), 20);
+        assert!(simulation.mean_draws_to_first_win() >= 3.0);
+    }
+
+    #[test]
+    fn simulate_rejects_mask_outside_boards() {
+        let rule = "mask:00001".parse().unwrap();
+
+        assert_eq!(
+            simulate(&options(5), 10, &rule).unwrap_err(),
+            "Mask 0 has cell 0,4 outside a 3x4 board!"
+        );
+    }
+
+    #[test]
+    fn simulate_rejects_zero_games() {
+        assert_eq!(
+            simulate(&options(5), 0, &WinRule::Lines).unwrap_err(),
+            "Cannot simulate 0 games!"
+        );
+    }
+
+    #[test]
+    fn first_winners_stops_at_first_winning_draw() {
+        let game = generate_game(&options(5)).unwrap();
+        let wins = play(&game.lucky_numbers, game.gameboards(), &WinRule::Lines);
+        let first_draw = wins[0].draw_index;
+        let expected: Vec<usize> = wins
+            .iter()
+            .take_while(|win| win.draw_index == first_draw)
+            .map(|win| win.board_index)
+            .collect();
+
+        let actual = first_winners(&game.lucky_numbers, game.gameboards(), &WinRule::Lines);
+        assert_eq!(actual, Some((first_draw, expected)));
+    }
+}
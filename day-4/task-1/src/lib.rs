@@ -0,0 +1,983 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+mod client;
+mod generator;
+mod protocol;
+mod ranking;
+mod render;
+mod server;
+mod state;
+mod win_rule;
+
+pub use client::Client;
+pub use generator::{generate_game, simulate, GeneratedGame, GeneratorOptions, Simulation};
+pub use protocol::{ClientMessage, ServerMessage};
+pub use ranking::rank_boards;
+pub use render::Renderer;
+pub use server::{Server, ServerOptions};
+pub use state::GameState;
+pub use win_rule::{WinRule, WinningPattern};
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+enum Field {
+    Unmarked(u32),
+    Marked(u32),
+}
+
+impl Field {
+    fn value(&self) -> u32 {
+        match self {
+            Field::Unmarked(value) | Field::Marked(value) => *value,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// A bingo board. Besides the fields it keeps where every number sits and how many
+/// cells are still unmarked in each row and column, so marking a number and checking
+/// its row and column don't need to scan the board. A number may sit in several cells.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Gameboard {
+    data: Vec<Vec<Field>>,
+    /// Every cell by its number, sorted by number and then row major. A flat list keeps
+    /// a board in a few allocations where a map of lists took one per number.
+    positions: Vec<(u32, Position)>,
+    unmarked_in_rows: Vec<usize>,
+    unmarked_in_columns: Vec<usize>,
+}
+
+impl Gameboard {
+    fn build(numbers: &mut Vec<Vec<u32>>) -> Gameboard {
+        let mut data: Vec<Vec<Field>> = vec![];
+        for values in numbers {
+            let mut row: Vec<Field> = vec![];
+            for &mut value in values {
+                row.push(Field::Unmarked(value));
+            }
+            data.push(row.to_vec());
+        }
+        Gameboard::from_data(data)
+    }
+
+    fn from_data(data: Vec<Vec<Field>>) -> Gameboard {
+        let columns = data.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut positions = vec![];
+        let mut unmarked_in_rows = vec![0; data.len()];
+        let mut unmarked_in_columns = vec![0; columns];
+        for (x, row) in data.iter().enumerate() {
+            for (y, field) in row.iter().enumerate() {
+                let value = match field {
+                    Field::Unmarked(value) => {
+                        unmarked_in_rows[x] += 1;
+                        unmarked_in_columns[y] += 1;
+                        value
+                    }
+                    Field::Marked(value) => value,
+                };
+                positions.push((*value, Position { x, y }));
+            }
+        }
+        positions.sort_by_key(|(value, _)| *value);
+
+        Gameboard {
+            data,
+            positions,
+            unmarked_in_rows,
+            unmarked_in_columns,
+        }
+    }
+
+    fn size(&self) -> BoardSize {
+        BoardSize {
+            rows: self.data.len(),
+            columns: self.unmarked_in_columns.len(),
+        }
+    }
+
+    fn is_row_all_marked(&self, row_index: usize) -> bool {
+        self.unmarked_in_rows.get(row_index) == Some(&0)
+    }
+
+    fn is_column_all_marked(&self, column_index: usize) -> bool {
+        self.unmarked_in_columns.get(column_index) == Some(&0)
+    }
+
+    /// Marks every unmarked cell holding `number` and returns their positions.
+    fn check_number(&mut self, number: u32) -> Vec<Position> {
+        let mut marked = vec![];
+        let start = self.positions.partition_point(|(value, _)| *value < number);
+        let cells = self.positions[start..].iter().take_while(|(value, _)| *value == number);
+        for (_, position) in cells {
+            let field = &mut self.data[position.x][position.y];
+            if let Field::Unmarked(value) = *field {
+                *field = Field::Marked(value);
+                self.unmarked_in_rows[position.x] -= 1;
+                self.unmarked_in_columns[position.y] -= 1;
+                marked.push(position.clone());
+            }
+        }
+
+        marked
+    }
+
+    /// Marks `number` and returns the pattern it completes, checking the row and column
+    /// of every cell that got marked.
+    fn mark(&mut self, number: u32, rule: &WinRule) -> Option<WinningPattern> {
+        self.check_number(number)
+            .iter()
+            .find_map(|position| self.winning_pattern(position, rule))
+    }
+
+    /// Digits of the widest number on the board.
+    fn width(&self) -> usize {
+        self.data.iter()
+            .flatten()
+            .map(|field| field.value().to_string().len())
+            .max()
+            .unwrap_or(1)
+    }
+
+    pub fn sum_unmarked(&self) -> u32 {
+        self.data.iter()
+            .flatten()
+            .map(|field| match field {
+                Field::Unmarked(value) => value,
+                _ => &0,
+            })
+            .sum()
+    }
+}
+
+/// Only the fields are written out. The positions and counters are rebuilt from them
+/// when reading a board back.
+impl Serialize for Gameboard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+/// Only accepts boards with at least one cell and rows of equal length.
+impl<'de> Deserialize<'de> for Gameboard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = Vec::<Vec<Field>>::deserialize(deserializer)?;
+        let columns = data.first().map_or(0, Vec::len);
+        if columns == 0 || data.iter().any(|row| row.len() != columns) {
+            return Err(de::Error::custom("Board rows are empty or differ in length!"));
+        }
+        Ok(Gameboard::from_data(data))
+    }
+}
+
+/// Prints the board as a grid with numbers right aligned and marked numbers in brackets,
+/// the way `Renderer` draws it without colour.
+impl fmt::Display for Gameboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let renderer = Renderer::default();
+        write!(f, "{}", renderer.render_board(self, None, &WinRule::Lines))
+    }
+}
+
+/// What board parsing does with a number appearing more than once on a board.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Duplicates {
+    /// Keeps the board, a draw marks every cell holding the number.
+    #[default]
+    MarkAll,
+    /// Rejects the board as malformed.
+    Reject,
+}
+
+impl FromStr for Duplicates {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "mark-all" => Ok(Duplicates::MarkAll),
+            "reject" => Ok(Duplicates::Reject),
+            _ => Err(format!("Unknown duplicates mode {}!", name)),
+        }
+    }
+}
+
+/// Rows and columns of a board, written as `5x5`. Boards don't have to be square.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardSize {
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl fmt::Display for BoardSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.rows, self.columns)
+    }
+}
+
+impl FromStr for BoardSize {
+    type Err = String;
+
+    fn from_str(size: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Unknown board size {}!", size);
+        let (rows, columns) = size.split_once('x').ok_or_else(error)?;
+        match (rows.parse(), columns.parse()) {
+            (Ok(rows), Ok(columns)) if rows > 0 && columns > 0 => Ok(BoardSize { rows, columns }),
+            _ => Err(error()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoardOptions {
+    pub duplicates: Duplicates,
+    /// Size every board must have. Without it all boards must match the first one.
+    pub size: Option<BoardSize>,
+}
+
+/// A malformed board. Boards are counted from zero and lines from one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoardError {
+    /// A row whose length differs from the first row of its board.
+    RaggedRow {
+        board: usize,
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A board of the wrong size, reported on its first line.
+    WrongSize {
+        board: usize,
+        line: usize,
+        expected: BoardSize,
+        found: BoardSize,
+    },
+    DuplicateNumber {
+        board: usize,
+        line: usize,
+        number: u32,
+    },
+    InvalidNumber {
+        board: usize,
+        line: usize,
+        token: String,
+    },
+    InvalidDraw {
+        line: usize,
+        token: String,
+    },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::RaggedRow {
+                board,
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "board {}, line {}: expected {} numbers but found {}",
+                board, line, expected, found
+            ),
+            BoardError::WrongSize {
+                board,
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "board {}, line {}: expected a {} board but found {}",
+                board, line, expected, found
+            ),
+            BoardError::DuplicateNumber { board, line, number } => write!(
+                f,
+                "board {}, line {}: {} appears more than once",
+                board, line, number
+            ),
+            BoardError::InvalidNumber { board, line, token } => write!(
+                f,
+                "board {}, line {}: {:?} is not a number",
+                board, line, token
+            ),
+            BoardError::InvalidDraw { line, token } => {
+                write!(f, "line {}: draw {:?} is not a number", line, token)
+            }
+        }
+    }
+}
+
+impl Error for BoardError {}
+
+/// Checks that the board is a rectangle of the expected size. The first board sets the
+/// size when none is given.
+fn validate_size(
+    board: usize,
+    rows: &[(usize, Vec<u32>)],
+    size: &mut Option<BoardSize>,
+) -> Result<(), BoardError> {
+    let (first_line, first_row) = &rows[0];
+    for (line, row) in rows {
+        if row.len() != first_row.len() {
+            return Err(BoardError::RaggedRow {
+                board,
+                line: *line,
+                expected: first_row.len(),
+                found: row.len(),
+            });
+        }
+    }
+    let found = BoardSize {
+        rows: rows.len(),
+        columns: first_row.len(),
+    };
+    match size {
+        Some(expected) if *expected != found => Err(BoardError::WrongSize {
+            board,
+            line: *first_line,
+            expected: *expected,
+            found,
+        }),
+        _ => {
+            *size = Some(found);
+            Ok(())
+        }
+    }
+}
+
+fn finish_gameboard(
+    board: usize,
+    rows: Vec<(usize, Vec<u32>)>,
+    size: &mut Option<BoardSize>,
+    options: &BoardOptions,
+) -> Result<Gameboard, BoardError> {
+    validate_size(board, &rows, size)?;
+    if options.duplicates == Duplicates::Reject {
+        let mut seen = HashSet::new();
+        for (line, row) in &rows {
+            if let Some(&number) = row.iter().find(|&&number| !seen.insert(number)) {
+                return Err(BoardError::DuplicateNumber { board, line: *line, number });
+            }
+        }
+    }
+    let mut numbers = rows.into_iter().map(|(_, row)| row).collect();
+
+    Ok(Gameboard::build(&mut numbers))
+}
+
+/// Builds the boards from numbered lines, a board ending at a blank line or at the end
+/// of the input. Lines holding only whitespace count as blank, any run of them separates
+/// two boards, and numbers may be separated by any whitespace, so `\r\n` line endings
+/// and tabs are accepted.
+fn parse_board_lines<I: Iterator<Item = (usize, String)>>(
+    lines: I,
+    options: &BoardOptions,
+) -> Result<Vec<Gameboard>, BoardError> {
+    let mut size = options.size;
+    input::blocks(lines)
+        .enumerate()
+        .map(|(board, block)| {
+            let rows = input::parse_grid(&block).map_err(|error| BoardError::InvalidNumber {
+                board,
+                line: error.line,
+                token: error.token,
+            })?;
+            finish_gameboard(board, rows, &mut size, options)
+        })
+        .collect()
+}
+
+/// Parses boards only, numbering lines from the start of `reader`.
+pub fn parse_gameboards<T: BufRead>(reader: &mut T, options: &BoardOptions) -> Result<Vec<Gameboard>, BoardError> {
+    parse_board_lines(input::lines(reader), options)
+}
+
+pub fn build_gameboards<T: BufRead>(reader: &mut T) -> Vec<Gameboard> {
+    parse_gameboards(reader, &BoardOptions::default()).expect("Cannot build gameboards!")
+}
+
+/// Parses a whole game: the comma separated draws on the first non-blank line, then the
+/// boards.
+pub fn parse_game<T: BufRead>(reader: &mut T, options: &BoardOptions) -> Result<(Vec<u32>, Vec<Gameboard>), BoardError> {
+    match input::split_header(input::lines(reader)) {
+        Some(((line_number, header), lines)) => {
+            let lucky_numbers = input::parse_separated(line_number, &header, ',').map_err(|error| {
+                BoardError::InvalidDraw {
+                    line: error.line,
+                    token: error.token,
+                }
+            })?;
+            Ok((lucky_numbers, parse_board_lines(lines, options)?))
+        }
+        None => Ok((vec![], vec![])),
+    }
+}
+
+fn check_number<'a>(lucky_number: u32, gameboards: &'a mut [Gameboard], rule: &WinRule) -> Option<&'a Gameboard> {
+    let index = gameboards
+        .iter_mut()
+        .position(|gameboard| gameboard.mark(lucky_number, rule).is_some())?;
+    Some(&gameboards[index])
+}
+
+pub fn find_winning_board(lucky_numbers: Vec<u32>, mut gameboards: Vec<Gameboard>, rule: &WinRule) -> Option<(u32, Gameboard)> {
+    for lucky_number in lucky_numbers {
+        if let Some(gameboard) = check_number(lucky_number, &mut gameboards, rule) {
+            return Some((lucky_number, (*gameboard).clone()));
+        }
+    }
+    None
+}
+
+/// A board completing a pattern. Boards, draws, rows, columns and masks are all
+/// counted from zero.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Win {
+    pub board_index: usize,
+    pub number: u32,
+    pub draw_index: usize,
+    pub pattern: WinningPattern,
+    pub score: u32,
+}
+
+impl fmt::Display for Win {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "draw {}: {} completes {} on board {}, score {}",
+            self.draw_index, self.number, self.pattern, self.board_index, self.score
+        )
+    }
+}
+
+/// Inverted index from every number to the boards holding it, in board order.
+fn index_boards(gameboards: &[Gameboard]) -> HashMap<u32, Vec<usize>> {
+    let mut boards_by_number: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (board_index, gameboard) in gameboards.iter().enumerate() {
+        for cells in gameboard.positions.chunk_by(|(a, _), (b, _)| a == b) {
+            let number = cells[0].0;
+            boards_by_number.entry(number).or_default().push(board_index);
+        }
+    }
+    boards_by_number
+}
+
+/// Keeps drawing numbers until every board has won or the draws run out, and returns
+/// the wins in the order they happened. Boards winning on the same draw are listed in
+/// board order, and a board stops playing once it has won. Each draw only visits the
+/// boards holding the number.
+pub fn play(lucky_numbers: &[u32], gameboards: Vec<Gameboard>, rule: &WinRule) -> Vec<Win> {
+    let mut state = GameState::new(lucky_numbers.to_vec(), gameboards, rule.clone());
+    state.advance(lucky_numbers.len());
+    state.wins
+}
+
+pub fn score(winning_board: Option<(u32, Gameboard)>) -> u32 {
+    if let Some((lucky_number, gameboard)) = winning_board {
+        gameboard.sum_unmarked() * lucky_number
+    } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn gameboard_build_returns_valid_gameboard() {
+        let mut data = vec![vec![1, 2], vec![3, 4]];
+        let expected = Gameboard::from_data(vec![
+            vec![Field::Unmarked(1), Field::Unmarked(2)],
+            vec![Field::Unmarked(3), Field::Unmarked(4)],
+        ]);
+
+        let actual = Gameboard::build(&mut data);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn build_gameboards_returns_vector_of_gameboards() {
+        let mut input = "10  1\n12 17\n\n 9  7\n16 19\n\n".as_bytes();
+        let actual = build_gameboards(&mut input);
+        let expected = vec![
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(10), Field::Unmarked(1)],
+                vec![Field::Unmarked(12), Field::Unmarked(17)],
+            ]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(9), Field::Unmarked(7)],
+                vec![Field::Unmarked(16), Field::Unmarked(19)],
+            ]),
+        ];
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual, expected);
+    }
+
+    #[test_case(Gameboard::from_data(vec![vec![Field::Marked(10), Field::Marked(1), Field::Marked(2)]]) => true)]
+    #[test_case(Gameboard::from_data(vec![vec![Field::Marked(10), Field::Unmarked(1), Field::Marked(2)]]) => false)]
+    #[test_case(Gameboard::from_data(vec![vec![Field::Unmarked(1), Field::Unmarked(11), Field::Unmarked(2)]]) => false)]
+    fn is_row_all_marked_returns_expected(gameboard: Gameboard) -> bool {
+        gameboard.is_row_all_marked(0)
+    }
+
+    #[test_case(Gameboard::from_data(vec![vec![Field::Marked(10), Field::Marked(1)], vec![Field::Marked(12), Field::Unmarked(8)]]), 1 => false)]
+    #[test_case(Gameboard::from_data(vec![vec![Field::Marked(10), Field::Marked(1)], vec![Field::Marked(12), Field::Unmarked(8)]]), 0 => true)]
+    fn is_column_all_marked_returns_expected(gameboard: Gameboard, column_index: usize) -> bool {
+        gameboard.is_column_all_marked(column_index)
+    }
+
+    #[test]
+    fn gameboard_check_number_returns_expected() {
+        let mut gameboard = Gameboard::from_data(vec![
+            vec![Field::Unmarked(2), Field::Unmarked(1)],
+            vec![Field::Unmarked(10), Field::Marked(4)],
+        ]);
+
+        assert_eq!(gameboard.check_number(1), vec![Position { x: 0, y: 1 }]);
+        let actual = match gameboard.data.first() {
+            Some(field) => match field.get(1) {
+                Some(field) => field,
+                _ => &Field::Unmarked(0),
+            },
+            _ => &Field::Unmarked(0),
+        };
+        assert_eq!(actual, &Field::Marked(1));
+        assert_eq!(gameboard.check_number(2), vec![Position { x: 0, y: 0 }]);
+        assert_eq!(gameboard.check_number(10), vec![Position { x: 1, y: 0 }]);
+        assert_eq!(gameboard.check_number(4), vec![]);
+    }
+
+    #[test]
+    fn sum_unmarked_return_expected() {
+        let gameboard = Gameboard::from_data(vec![
+            vec![Field::Unmarked(1), Field::Unmarked(2), Field::Marked(3)],
+            vec![Field::Marked(4), Field::Marked(5), Field::Marked(6)],
+            vec![Field::Marked(7), Field::Unmarked(8), Field::Unmarked(9)],
+        ]);
+        let expected = 20;
+        let actual = gameboard.sum_unmarked();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn gameboard_display_brackets_marked_numbers() {
+        let gameboard = Gameboard::from_data(vec![
+            vec![Field::Unmarked(1), Field::Marked(22), Field::Unmarked(3)],
+            vec![Field::Marked(14), Field::Unmarked(5), Field::Marked(6)],
+        ]);
+        let expected = "  1 [22]  3\n[14]  5 [ 6]\n";
+
+        assert_eq!(gameboard.to_string(), expected);
+    }
+
+    #[test]
+    fn check_number_returns_expected() {
+        let mut gameboards = vec![
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(1), Field::Unmarked(2)],
+                vec![Field::Unmarked(3), Field::Marked(4)],
+            ]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(1), Field::Unmarked(2)],
+                vec![Field::Unmarked(5), Field::Marked(4)],
+            ]),
+        ];
+        let expected = Gameboard::from_data(vec![
+            vec![Field::Unmarked(1), Field::Unmarked(2)],
+            vec![Field::Marked(5), Field::Marked(4)],
+        ]);
+
+        let actual = check_number(5, &mut gameboards, &WinRule::Lines);
+        assert_eq!(actual, Some(&expected));
+    }
+
+    #[test]
+    fn find_winning_board_return_expected() {
+        let lucky_numbers = vec![1, 4, 3, 5, 9];
+        let gameboards = vec![
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(1), Field::Unmarked(2)],
+                vec![Field::Unmarked(3), Field::Unmarked(4)],
+            ]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(1), Field::Unmarked(2)],
+                vec![Field::Unmarked(5), Field::Unmarked(4)],
+            ]),
+        ];
+
+        let actual = find_winning_board(lucky_numbers, gameboards, &WinRule::Lines);
+        assert_eq!(
+            actual,
+            Some((
+                3,
+                Gameboard::from_data(vec![
+                    vec![Field::Marked(1), Field::Unmarked(2)],
+                    vec![Field::Marked(3), Field::Marked(4)],
+                ])
+            ))
+        )
+    }
+
+    #[test]
+    fn build_gameboards_keeps_board_without_trailing_blank_line() {
+        let mut input = "10  1\n12 17\n\n 9  7\n16 19".as_bytes();
+        let actual = build_gameboards(&mut input);
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(
+            actual[1].data[1],
+            vec![Field::Unmarked(16), Field::Unmarked(19)]
+        );
+    }
+
+    #[test]
+    fn index_boards_returns_boards_per_number() {
+        let gameboards = build_gameboards(&mut "1 2\n3 4\n\n4 5\n6 1\n".as_bytes());
+
+        let actual = index_boards(&gameboards);
+        assert_eq!(actual.len(), 6);
+        assert_eq!(actual[&1], vec![0, 1]);
+        assert_eq!(actual[&4], vec![0, 1]);
+        assert_eq!(actual[&5], vec![1]);
+    }
+
+    #[test]
+    fn gameboard_check_number_updates_counters() {
+        let mut gameboard = Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]]);
+
+        gameboard.check_number(2);
+        gameboard.check_number(4);
+        assert_eq!(gameboard.unmarked_in_rows, vec![1, 1]);
+        assert_eq!(gameboard.unmarked_in_columns, vec![2, 0]);
+        assert!(gameboard.is_column_all_marked(1));
+        assert!(!gameboard.is_row_all_marked(0));
+    }
+
+    #[test]
+    fn play_returns_wins_in_order() {
+        let lucky_numbers = vec![1, 4, 3, 5, 9];
+        let gameboards = vec![
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(1), Field::Unmarked(2)],
+                vec![Field::Unmarked(3), Field::Unmarked(4)],
+            ]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(1), Field::Unmarked(2)],
+                vec![Field::Unmarked(5), Field::Unmarked(4)],
+            ]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(9), Field::Unmarked(4)],
+                vec![Field::Unmarked(6), Field::Unmarked(2)],
+            ]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(7), Field::Unmarked(8)],
+                vec![Field::Unmarked(6), Field::Unmarked(2)],
+            ]),
+        ];
+        let expected = vec![
+            Win {
+                board_index: 0,
+                number: 3,
+                draw_index: 2,
+                pattern: WinningPattern::Row(1),
+                score: 6,
+            },
+            Win {
+                board_index: 1,
+                number: 5,
+                draw_index: 3,
+                pattern: WinningPattern::Row(1),
+                score: 10,
+            },
+            Win {
+                board_index: 2,
+                number: 9,
+                draw_index: 4,
+                pattern: WinningPattern::Row(0),
+                score: 72,
+            },
+        ];
+
+        assert_eq!(play(&lucky_numbers, gameboards, &WinRule::Lines), expected);
+    }
+
+    #[test]
+    fn play_lists_boards_winning_on_same_draw() {
+        let lucky_numbers = vec![2, 1];
+        let gameboards = vec![
+            Gameboard::from_data(vec![vec![Field::Unmarked(1)], vec![Field::Unmarked(3)]]),
+            Gameboard::from_data(vec![
+                vec![Field::Unmarked(2), Field::Unmarked(1)],
+                vec![Field::Unmarked(5), Field::Unmarked(6)],
+            ]),
+        ];
+
+        let actual = play(&lucky_numbers, gameboards, &WinRule::Lines);
+        assert_eq!(actual.len(), 2);
+        assert_eq!(
+            (actual[0].board_index, actual[0].pattern),
+            (0, WinningPattern::Row(0))
+        );
+        assert_eq!(
+            (actual[1].board_index, actual[1].pattern),
+            (1, WinningPattern::Row(0))
+        );
+        assert_eq!(actual[1].draw_index, 1);
+    }
+
+    #[test]
+    fn win_display_returns_transcript_line() {
+        let win = Win {
+            board_index: 2,
+            number: 24,
+            draw_index: 11,
+            pattern: WinningPattern::Column(3),
+            score: 4512,
+        };
+
+        assert_eq!(
+            win.to_string(),
+            "draw 11: 24 completes column 3 on board 2, score 4512"
+        );
+    }
+
+    #[test]
+    fn play_plays_example() {
+        let mut input = "\
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7
+"
+        .as_bytes();
+        let lucky_numbers = vec![
+            7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18, 20, 8, 19,
+            3, 26, 1,
+        ];
+        let gameboards = build_gameboards(&mut input);
+
+        assert_eq!(
+            score(find_winning_board(
+                lucky_numbers.clone(),
+                gameboards.clone(),
+                &WinRule::Lines
+            )),
+            4512
+        );
+        let wins = play(&lucky_numbers, gameboards, &WinRule::Lines);
+        let order: Vec<usize> = wins.iter().map(|win| win.board_index).collect();
+        assert_eq!(order, vec![2, 0, 1]);
+        assert_eq!(wins[0].score, 4512);
+        assert_eq!(wins[0].pattern, WinningPattern::Row(0));
+        assert_eq!(wins[2].score, 1924);
+    }
+
+    #[test]
+    fn gameboard_check_number_marks_every_occurrence() {
+        let mut gameboard = Gameboard::build(&mut vec![vec![1, 2], vec![3, 1]]);
+
+        assert_eq!(
+            gameboard.check_number(1),
+            vec![Position { x: 0, y: 0 }, Position { x: 1, y: 1 }]
+        );
+        assert_eq!(gameboard.unmarked_in_rows, vec![1, 1]);
+        assert_eq!(gameboard.unmarked_in_columns, vec![1, 1]);
+        assert_eq!(gameboard.sum_unmarked(), 5);
+        assert_eq!(gameboard.check_number(1), vec![]);
+    }
+
+    #[test]
+    fn play_checks_lines_of_every_occurrence() {
+        let gameboards = vec![Gameboard::build(&mut vec![vec![5, 1], vec![2, 5]])];
+
+        let actual = play(&[5, 2], gameboards, &WinRule::Lines);
+        assert_eq!(
+            actual,
+            vec![Win {
+                board_index: 0,
+                number: 2,
+                draw_index: 1,
+                pattern: WinningPattern::Row(1),
+                score: 2
+            }]
+        );
+    }
+
+    #[test_case(Duplicates::MarkAll => Ok(2))]
+    #[test_case(Duplicates::Reject => Err(BoardError::DuplicateNumber { board: 1, line: 5, number: 5 }))]
+    fn parse_gameboards_handles_duplicates(duplicates: Duplicates) -> Result<usize, BoardError> {
+        let mut input = "1 2\n3 4\n\n5 6\n7 5\n".as_bytes();
+
+        parse_gameboards(
+            &mut input,
+            &BoardOptions {
+                duplicates,
+                ..Default::default()
+            },
+        )
+        .map(|gameboards| gameboards.len())
+    }
+
+    #[test]
+    fn parse_game_numbers_lines_from_draws() {
+        let mut input = "7,4\n\n1 2\n3 1\n".as_bytes();
+        let options = BoardOptions {
+            duplicates: Duplicates::Reject,
+            ..Default::default()
+        };
+
+        let actual = parse_game(&mut input, &options);
+        assert_eq!(
+            actual,
+            Err(BoardError::DuplicateNumber {
+                board: 0,
+                line: 4,
+                number: 1
+            })
+        );
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "board 0, line 4: 1 appears more than once"
+        );
+    }
+
+    #[test]
+    fn parse_game_returns_draws_and_boards() {
+        let mut input = "7,4,9\n\n1 2\n3 4\n".as_bytes();
+
+        let (lucky_numbers, gameboards) = parse_game(&mut input, &BoardOptions::default()).unwrap();
+        assert_eq!(lucky_numbers, vec![7, 4, 9]);
+        assert_eq!(
+            gameboards,
+            vec![Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]])]
+        );
+    }
+
+    #[test_case("mark-all" => Ok(Duplicates::MarkAll))]
+    #[test_case("reject" => Ok(Duplicates::Reject))]
+    #[test_case("ignore" => Err("Unknown duplicates mode ignore!".to_string()))]
+    fn duplicates_from_str(name: &str) -> Result<Duplicates, String> {
+        name.parse()
+    }
+
+    #[test_case("1 2 3\n4 5\n", None => BoardError::RaggedRow { board: 0, line: 2, expected: 3, found: 2 })]
+    #[test_case("1 2\n3 4\n\n5 6 7\n8 9 10\n", None => BoardError::WrongSize {
+        board: 1, line: 4, expected: BoardSize { rows: 2, columns: 2 }, found: BoardSize { rows: 2, columns: 3 }
+    })]
+    #[test_case("1 2\n3 4\n\n5 6\n", None => BoardError::WrongSize {
+        board: 1, line: 4, expected: BoardSize { rows: 2, columns: 2 }, found: BoardSize { rows: 1, columns: 2 }
+    })]
+    #[test_case("1 2\n3 4\n", Some(BoardSize { rows: 2, columns: 3 }) => BoardError::WrongSize {
+        board: 0, line: 1, expected: BoardSize { rows: 2, columns: 3 }, found: BoardSize { rows: 2, columns: 2 }
+    })]
+    fn parse_gameboards_rejects_malformed_shape(
+        input: &str,
+        size: Option<BoardSize>,
+    ) -> BoardError {
+        let options = BoardOptions {
+            size,
+            ..Default::default()
+        };
+
+        parse_gameboards(&mut input.as_bytes(), &options).unwrap_err()
+    }
+
+    #[test]
+    fn board_error_display_returns_location() {
+        let error = BoardError::WrongSize {
+            board: 3,
+            line: 20,
+            expected: BoardSize {
+                rows: 5,
+                columns: 5,
+            },
+            found: BoardSize {
+                rows: 4,
+                columns: 5,
+            },
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "board 3, line 20: expected a 5x5 board but found 4x5"
+        );
+    }
+
+    #[test]
+    fn play_supports_rectangular_boards() {
+        let mut input = "1 2 3\n4 5 6\n\n7 8 9\n1 5 6\n".as_bytes();
+        let options = BoardOptions {
+            size: Some("2x3".parse().unwrap()),
+            ..Default::default()
+        };
+        let gameboards = parse_gameboards(&mut input, &options).unwrap();
+
+        let actual = play(&[2, 5, 1, 8, 9], gameboards, &WinRule::Lines);
+        let patterns: Vec<(usize, WinningPattern)> = actual
+            .iter()
+            .map(|win| (win.board_index, win.pattern))
+            .collect();
+        assert_eq!(
+            patterns,
+            vec![
+                (0, WinningPattern::Column(1)),
+                (1, WinningPattern::Column(1))
+            ]
+        );
+        assert_eq!(actual[0].score, 14 * 5);
+    }
+
+    #[test_case("5x5" => Ok(BoardSize { rows: 5, columns: 5 }))]
+    #[test_case("3x7" => Ok(BoardSize { rows: 3, columns: 7 }))]
+    #[test_case("0x5" => Err("Unknown board size 0x5!".to_string()))]
+    #[test_case("5" => Err("Unknown board size 5!".to_string()))]
+    fn board_size_from_str(size: &str) -> Result<BoardSize, String> {
+        size.parse()
+    }
+
+    #[test_case("1 2\n3 x4\n" => BoardError::InvalidNumber { board: 0, line: 2, token: "x4".to_string() })]
+    #[test_case("1 2\n3 4\n\n5 -6\n7 8\n" => BoardError::InvalidNumber { board: 1, line: 4, token: "-6".to_string() })]
+    fn parse_gameboards_rejects_invalid_numbers(input: &str) -> BoardError {
+        parse_gameboards(&mut input.as_bytes(), &BoardOptions::default()).unwrap_err()
+    }
+
+    #[test]
+    fn parse_game_rejects_invalid_draw() {
+        let actual = parse_game(
+            &mut "\n7,4,nine\n\n1 2\n".as_bytes(),
+            &BoardOptions::default(),
+        );
+
+        assert_eq!(
+            actual,
+            Err(BoardError::InvalidDraw {
+                line: 2,
+                token: "nine".to_string()
+            })
+        );
+    }
+}
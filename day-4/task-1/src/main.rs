@@ -1,306 +1,259 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-#[derive(Clone, PartialEq, Debug)]
-enum Field {
-    Unmarked(u32),
-    Marked(u32),
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::process;
+use std::time::Duration;
+use task_1::{
+    find_winning_board, generate_game, parse_game, play, rank_boards, score, simulate, BoardOptions,
+    Client, Duplicates, GameState, Gameboard, GeneratorOptions, Renderer, Server, ServerOptions,
+    WinRule,
+};
+use verify::Part;
+
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .map(|i| args.get(i + 1).expect("Missing option value!").as_str())
 }
 
-#[derive(PartialEq, Debug)]
-struct Position {
-    x: usize,
-    y: usize,
+fn win_rule(args: &[String]) -> WinRule {
+    match option_value(args, "--win-rule") {
+        Some(rule) => rule.parse().expect("Cannot parse win rule!"),
+        None => WinRule::default(),
+    }
 }
 
-#[derive(PartialEq, Debug, Clone)]
-struct Gameboard {
-    data: Vec<Vec<Field>>,
+fn generator_options(args: &[String]) -> GeneratorOptions {
+    let defaults = GeneratorOptions::default();
+    GeneratorOptions {
+        boards: option_value(args, "--boards").map_or(defaults.boards, |boards| {
+            boards.parse().expect("Cannot parse number of boards!")
+        }),
+        size: option_value(args, "--size").map_or(defaults.size, |size| {
+            size.parse().expect("Cannot parse board size!")
+        }),
+        range: option_value(args, "--range").map_or(defaults.range, |range| {
+            range.parse().expect("Cannot parse number range!")
+        }),
+        seed: option_value(args, "--seed").map_or(defaults.seed, |seed| {
+            seed.parse().expect("Cannot parse seed!")
+        }),
+    }
 }
 
-impl Gameboard {
-    fn build(numbers: &mut Vec<Vec<u32>>) -> Gameboard {
-        let mut data: Vec<Vec<Field>> = vec![];
-        for values in numbers {
-            let mut row: Vec<Field> = vec![];
-            for &mut value in values {
-                row.push(Field::Unmarked(value));
-            }
-            data.push(row.to_vec());
-        }
-        Gameboard{
-            data,
-        }
+fn renderer(args: &[String]) -> Renderer {
+    Renderer {
+        colour: !args.iter().any(|arg| arg == "--no-colour"),
+        delay: option_value(args, "--animate").map(|millis| {
+            Duration::from_millis(millis.parse().expect("Cannot parse animation delay!"))
+        }),
     }
+}
 
-    fn is_row_all_marked(&self, row_index: usize) -> bool {
-        if let Some(row) = self.data.get(row_index) {
-            !row.iter().any(|x| match x {
-                Field::Unmarked(_) => true,
-                _ => false,
-            })
-        } else {
-            return false;
-        }
+fn generate(args: &[String]) {
+    let game = generate_game(&generator_options(args)).expect("Cannot generate game!");
+    match option_value(args, "--output") {
+        Some(path) => fs::write(path, game.to_string()).expect("Cannot write game!"),
+        None => print!("{}", game),
     }
+}
 
-    fn is_column_all_marked(&self, column_index: usize) -> bool {
-        !self.data.iter().any(|row| match row.get(column_index) {
-            Some(field) => match field {
-                Field::Unmarked(_) => true,
-                _ => false,
-            },
-            _ => false,
-        })
-    }
+fn run_simulation(args: &[String]) {
+    let games = option_value(args, "--games").map_or(1000, |games| {
+        games.parse().expect("Cannot parse number of games!")
+    });
+    let simulation = simulate(&generator_options(args), games, &win_rule(args))
+        .expect("Cannot simulate games!");
+    print!("{}", simulation);
+}
 
-    fn check_number(&mut self, number: u32) -> Option<Position> {
-        for (i, row) in self.data.iter_mut().enumerate() {
-            for (j, field) in row.iter_mut().enumerate() {
-                if let Field::Unmarked(value) =  field {
-                    if *value == number {
-                        *field = Field::Marked(*value);
-                        return Option::Some(Position{x: i, y: j});
-                    }
-                }
-            }
-        }
+fn board_options(args: &[String]) -> BoardOptions {
+    let duplicates = match option_value(args, "--duplicates") {
+        Some(duplicates) => duplicates.parse().expect("Cannot parse duplicates mode!"),
+        None => Duplicates::default(),
+    };
+    let size = option_value(args, "--size").map(|size| size.parse().expect("Cannot parse board size!"));
+    BoardOptions { duplicates, size }
+}
 
-        Option::None
-    }
+fn draws(args: &[String]) -> usize {
+    option_value(args, "--draws").map_or(usize::MAX, |draws| {
+        draws.parse().expect("Cannot parse number of draws!")
+    })
+}
 
-    fn sum_unmarked(&self) -> u32 {
-        self.data.iter()
-            .flatten()
-            .map(|field| match field {
-                Field::Unmarked(value) => value,
-                _ => &0,
-            })
-            .sum()
+/// Plays the input's first `--draws` numbers and writes the game to `--output` as JSON.
+fn save(args: &[String]) {
+    let file = File::open("src/input.txt").expect("File cannot be opened!");
+    let (lucky_numbers, gameboards) =
+        parse_game(&mut BufReader::new(file), &board_options(args)).expect("Gameboard data is malformed!");
+    let rule = win_rule(args);
+    rule.check_boards(&gameboards).expect("Win rule doesn't fit the boards!");
+    let mut state = GameState::new(lucky_numbers, gameboards, rule);
+    for win in state.advance(draws(args)) {
+        println!("{}", win);
     }
+    let path = option_value(args, "--output").expect("Missing --output!");
+    fs::write(path, state.to_json().expect("Cannot serialize game!")).expect("Cannot write game!");
 }
 
-fn build_gameboards<T: BufRead>(reader: &mut T) -> Vec<Gameboard> {
-    let mut numbers: Vec<Vec<u32>> = Vec::new();
-    let mut gameboards: Vec<Gameboard> = Vec::new();
-
-    for line in reader.lines() {
-        let line = line.expect("Cannot read gameboard data, file may be corrupted!");
-        if line.eq("") && numbers.len() > 0 {
-            gameboards.push(Gameboard::build(&mut numbers));
-            numbers = Vec::new();
-        } else {
-            let clean_numbers = parse_gameboard_data(&line);
-            if clean_numbers.len() > 0 {
-                numbers.push(clean_numbers.to_vec());
-            }
+/// Picks up the game saved in `--state` for another `--draws` numbers, or until it ends,
+/// and writes it back to `--output` if given.
+fn resume(args: &[String]) {
+    let path = option_value(args, "--state").expect("Missing --state!");
+    let json = fs::read_to_string(path).expect("Cannot read saved game!");
+    let mut state = GameState::from_json(&json).expect("Saved game is malformed!");
+    for win in state.advance(draws(args)) {
+        println!("{}", win);
+    }
+    match option_value(args, "--output") {
+        Some(path) => fs::write(path, state.to_json().expect("Cannot serialize game!"))
+            .expect("Cannot write game!"),
+        None => {
+            let last_score = state.wins.last().map_or(0, |win| win.score);
+            println!("The last winning board result is {}", last_score);
         }
     }
-    gameboards
 }
 
-fn parse_gameboard_data(data: &str) -> Vec<u32> {
-    data.split(" ")
-        .filter(|x| !x.eq(&""))
-        .map(|x| x.trim().parse::<u32>().unwrap())
-        .collect()
+fn address(args: &[String]) -> &str {
+    option_value(args, "--address").unwrap_or("127.0.0.1:7878")
 }
 
-fn check_number(lucky_number: u32, gameboards: &mut Vec<Gameboard>) -> Option<&Gameboard> {
-    for gameboard in gameboards {
-        if let Some(position) = gameboard.check_number(lucky_number) {
-            if gameboard.is_row_all_marked(position.x) || gameboard.is_column_all_marked(position.y) {
-                return Some(gameboard);
-            }
-        };
+/// Hosts a game with the input's draws for `--players` players.
+fn serve(args: &[String]) {
+    let file = File::open("src/input.txt").expect("File cannot be opened!");
+    let (lucky_numbers, _) =
+        parse_game(&mut BufReader::new(file), &BoardOptions::default()).expect("Gameboard data is malformed!");
+    let board_options = board_options(args);
+    let options = ServerOptions {
+        players: option_value(args, "--players").map_or(1, |players| {
+            players.parse().expect("Cannot parse number of players!")
+        }),
+        rule: win_rule(args),
+        size: board_options.size,
+        duplicates: board_options.duplicates,
+        auto_claim: args.iter().any(|arg| arg == "--auto-claim"),
+        timeout: option_value(args, "--timeout").map_or(ServerOptions::default().timeout, |seconds| {
+            Some(Duration::from_secs(seconds.parse().expect("Cannot parse timeout!")))
+        }),
+    };
+    let server = Server::bind(address(args)).expect("Cannot start server!");
+    println!("Listening on {}", server.local_addr().expect("Cannot read server address!"));
+    let wins = server.run(&lucky_numbers, &options).expect("Game was interrupted!");
+    for win in &wins {
+        println!("{}", win);
     }
-    None
 }
 
-fn find_winning_board(lucky_numbers: Vec<u32>, mut gameboards: Vec<Gameboard>) -> Option<(u32, Gameboard)> {
-    for lucky_number in lucky_numbers {
-        if let Some(gameboard) = check_number(lucky_number, &mut gameboards) {
-            return Some((lucky_number, (*gameboard).clone()));
-        }
+/// Joins a game with generated boards and claims their wins.
+fn join(args: &[String]) {
+    let game = generate_game(&generator_options(args)).expect("Cannot generate game!");
+    let client = Client::connect(address(args)).expect("Cannot connect to server!");
+    let wins = client.play(&game.boards, &win_rule(args)).expect("Game was interrupted!");
+    for win in &wins {
+        println!("{}", win);
     }
-    None
 }
 
 fn main() {
-    let file = File::open("src/input.txt").expect("File cannot be opened!");
-    let mut reader = BufReader::new(file);
-    let mut lucky_numbers = String::new();
-    reader.read_line(&mut lucky_numbers).expect("Cannot read lucky_numbers, file may be corrupted!");
-    let lucky_numbers = lucky_numbers
-        .split(",")
-        .map(|x| x.trim().parse::<u32>().unwrap())
-        .collect();
-    let gameboards = build_gameboards(&mut reader);
-    let winning_board = find_winning_board(lucky_numbers, gameboards);
-    let result = if let Some((lucky_number, gameboard)) = winning_board {
-        gameboard.sum_unmarked() * lucky_number
-    } else { 0 };
-    println!("The final result is {}", result);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use test_case::test_case;
-
-    #[test]
-    fn gameboard_build_returns_valid_gameboard() {
-        let mut data = vec![vec![1, 2], vec![3, 4]];
-        let expected = Gameboard {
-            data: vec![
-                vec![Field::Unmarked(1), Field::Unmarked(2)],
-                vec![Field::Unmarked(3), Field::Unmarked(4)],
-            ]
-        };
-
-        let actual = Gameboard::build(&mut data);
-
-        assert_eq!(actual, expected);
-    }
-
-    #[test_case("10 11 12 16 18 17" => vec![10, 11, 12, 16, 18, 17])]
-    #[test_case(" 9  7 17  6" => vec![9, 7, 17, 6])]
-    #[test_case(" 2 11  8\n\n" => vec![2, 11, 8])]
-    fn parse_gameboard_data_returns_vector_of_int(input: &str) -> Vec<u32> {
-        parse_gameboard_data(input)
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("generate") => generate(&args),
+        Some("simulate") => run_simulation(&args),
+        Some("save") => save(&args),
+        Some("resume") => resume(&args),
+        Some("serve") => serve(&args),
+        Some("join") => join(&args),
+        Some("verify") => {
+            if !verify_answers() {
+                process::exit(1);
+            }
+        }
+        _ => solve(&args),
     }
+}
 
-    #[test]
-    fn build_gameboards_returns_vector_of_gameboards() {
-        let mut input = "10  1\n12 17\n\n 9  7\n16 19\n\n".as_bytes();
-        let actual = build_gameboards(&mut input);
-        let expected = vec![
-            Gameboard{
-                data: vec![
-                    vec![Field::Unmarked(10), Field::Unmarked(1)],
-                    vec![Field::Unmarked(12), Field::Unmarked(17)],
-                ],
-            },
-            Gameboard{
-                data: vec![
-                    vec![Field::Unmarked(9), Field::Unmarked(7)],
-                    vec![Field::Unmarked(16), Field::Unmarked(19)],
-                ],
-            },
-        ];
-
-        assert_eq!(actual.len(), 2);
-        assert_eq!(actual, expected);
+fn solve(args: &[String]) {
+    let file = File::open("src/input.txt").expect("File cannot be opened!");
+    let mut reader = BufReader::new(file);
+    let rule = win_rule(args);
+    let (lucky_numbers, gameboards) =
+        parse_game(&mut reader, &board_options(args)).expect("Gameboard data is malformed!");
+    rule.check_boards(&gameboards).expect("Win rule doesn't fit the boards!");
+    let winning_board = find_winning_board(lucky_numbers.clone(), gameboards.clone(), &rule);
+    println!("The final result is {}", score(winning_board));
+    let wins = play(&lucky_numbers, gameboards.clone(), &rule);
+    let last_score = wins.last().map_or(0, |win| win.score);
+    println!("The last winning board result is {}", last_score);
+    if let Some(nth) = option_value(args, "--nth") {
+        let nth: usize = nth.parse().expect("Cannot parse winner number!");
+        match wins.get(nth) {
+            Some(win) => println!("Winner {}: {}", nth, win),
+            None => println!("Only {} boards have won!", wins.len()),
+        }
     }
-
-    #[test_case(Gameboard{ data: vec![vec![Field::Marked(10), Field::Marked(1), Field::Marked(2)]] } => true)]
-    #[test_case(Gameboard{ data: vec![vec![Field::Marked(10), Field::Unmarked(1), Field::Marked(2)]] } => false)]
-    #[test_case(Gameboard{ data: vec![vec![Field::Unmarked(1), Field::Unmarked(11), Field::Unmarked(2)]] } => false)]
-    fn is_row_all_marked_returns_expected(gameboard: Gameboard) -> bool {
-        gameboard.is_row_all_marked(0)
+    if args.iter().any(|arg| arg == "--transcript") {
+        for win in &wins {
+            println!("{}", win);
+        }
     }
-
-    #[test_case(Gameboard{ data: vec![vec![Field::Marked(10), Field::Marked(1)], vec![Field::Marked(12), Field::Unmarked(8)]] }, 1 => false)]
-    #[test_case(Gameboard{ data: vec![vec![Field::Marked(10), Field::Marked(1)], vec![Field::Marked(12), Field::Unmarked(8)]] }, 0 => true)]
-    fn is_column_all_marked_returns_expected(gameboard: Gameboard, column_index: usize) -> bool {
-        gameboard.is_column_all_marked(column_index)
+    if args.iter().any(|arg| arg == "--ranking") {
+        println!("Boards by the draw they would win on when playing alone:");
+        for win in rank_boards(&lucky_numbers, &gameboards, &rule) {
+            println!("{}", win);
+        }
     }
-
-    #[test]
-    fn gameboard_check_number_returns_expected() {
-        let mut gameboard = Gameboard {
-            data: vec![
-                vec![Field::Unmarked(2), Field::Unmarked(1)],
-                vec![Field::Unmarked(10), Field::Marked(4)],
-            ],
-        };
-
-        assert_eq!(gameboard.check_number(1), Some(Position{x: 0, y: 1}));
-        let actual = match gameboard.data.get(0) {
-            Some(field) => match field.get(1) {
-                Some(field) => field,
-                _ => &Field::Unmarked(0),
-            },
-            _ => &Field::Unmarked(0),
-        };
-        assert_eq!(actual, &Field::Marked(1));
-        assert_eq!(gameboard.check_number(2), Some(Position{x: 0, y: 0}));
-        assert_eq!(gameboard.check_number(10), Some(Position{x: 1, y: 0}));
-        assert_eq!(gameboard.check_number(4), None);
+    if args.iter().any(|arg| arg == "--render" || arg == "--animate") {
+        renderer(args)
+            .show_game(&mut io::stdout(), &lucky_numbers, gameboards, &rule)
+            .expect("Cannot render game!");
     }
+}
 
-    #[test]
-    fn sum_unmarked_return_expected() {
-        let gameboard = Gameboard{
-            data: vec![
-                vec![Field::Unmarked(1), Field::Unmarked(2), Field::Marked(3)],
-                vec![Field::Marked(4), Field::Marked(5), Field::Marked(6)],
-                vec![Field::Marked(7), Field::Unmarked(8), Field::Unmarked(9)],
-            ],
-        };
-        let expected = 20;
-        let actual = gameboard.sum_unmarked();
-
-        assert_eq!(actual, expected);
-    }
+fn bundled_game() -> (Vec<u32>, Vec<Gameboard>) {
+    let file = File::open("src/input.txt").expect("File cannot be opened!");
+    parse_game(&mut BufReader::new(file), &BoardOptions::default()).expect("Gameboard data is malformed!")
+}
 
-    #[test]
-    fn check_number_returns_expected() {
-        let mut gameboards = vec![
-            Gameboard{
-                data: vec![
-                    vec![Field::Unmarked(1), Field::Unmarked(2)],
-                    vec![Field::Unmarked(3), Field::Marked(4)],
-                ]
+/// Checks every way of solving both parts against `answers.toml`.
+fn verify_answers() -> bool {
+    let parts = [
+        Part {
+            name: "part_one",
+            variant: None,
+            solve: || {
+                let (lucky_numbers, gameboards) = bundled_game();
+                score(find_winning_board(lucky_numbers, gameboards, &WinRule::default())).to_string()
             },
-            Gameboard{
-                data: vec![
-                    vec![Field::Unmarked(1), Field::Unmarked(2)],
-                    vec![Field::Unmarked(5), Field::Marked(4)],
-                ]
+        },
+        Part {
+            name: "part_one",
+            variant: Some("ranking"),
+            solve: || {
+                let (lucky_numbers, gameboards) = bundled_game();
+                let wins = rank_boards(&lucky_numbers, &gameboards, &WinRule::default());
+                wins.first().map_or(0, |win| win.score).to_string()
             },
-        ];
-        let expected = Gameboard{
-            data: vec![
-                vec![Field::Unmarked(1), Field::Unmarked(2)],
-                vec![Field::Marked(5), Field::Marked(4)],
-            ]
-        };
-
-        let actual = check_number(5, &mut gameboards);
-        assert_eq!(actual, Some(&expected));
-    }
-
-    #[test]
-    fn find_winning_board_return_expected() {
-        let lucky_numbers = vec![1, 4, 3, 5, 9];
-        let gameboards = vec![
-            Gameboard{
-                data: vec![
-                    vec![Field::Unmarked(1), Field::Unmarked(2)],
-                    vec![Field::Unmarked(3), Field::Unmarked(4)],
-                ]
+        },
+        Part {
+            name: "part_two",
+            variant: None,
+            solve: || {
+                let (lucky_numbers, gameboards) = bundled_game();
+                let wins = play(&lucky_numbers, gameboards, &WinRule::default());
+                wins.last().map_or(0, |win| win.score).to_string()
             },
-            Gameboard{
-                data: vec![
-                    vec![Field::Unmarked(1), Field::Unmarked(2)],
-                    vec![Field::Unmarked(5), Field::Unmarked(4)],
-                ]
+        },
+        Part {
+            name: "part_two",
+            variant: Some("ranking"),
+            solve: || {
+                let (lucky_numbers, gameboards) = bundled_game();
+                let wins = rank_boards(&lucky_numbers, &gameboards, &WinRule::default());
+                wins.last().map_or(0, |win| win.score).to_string()
             },
-        ];
-
-        let actual = find_winning_board(lucky_numbers, gameboards);
-        assert_eq!(
-            actual,
-            Some((
-                3,
-                Gameboard{
-                    data: vec![
-                        vec![Field::Marked(1), Field::Unmarked(2)],
-                        vec![Field::Marked(3), Field::Marked(4)],
-                    ]
-                }
-            ))
-        )
-    }
+        },
+    ];
+    verify::run("answers.toml", &parts)
 }
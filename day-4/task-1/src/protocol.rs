@@ -0,0 +1,216 @@
+use crate::Win;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::str::FromStr;
+
+/// Lines a player sends to the bingo server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientMessage {
+    /// Registers a board, written as rows separated by `/`: `BOARD 1 2/3 4`.
+    Board(Vec<Vec<u32>>),
+    /// Done registering boards.
+    Ready,
+    /// Claims that a board won on the current draw.
+    Claim(usize),
+    /// Done with the current draw.
+    Next,
+}
+
+/// Lines the bingo server sends to a player.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    /// Index of a newly registered board, counted over all players.
+    Registered(usize),
+    Draw { draw_index: usize, number: u32 },
+    /// The claim for a board was accepted. The win is announced once every player is
+    /// done with the draw.
+    Claimed(usize),
+    Win(Win),
+    Error(String),
+    /// Every board has won or the draws ran out.
+    End,
+}
+
+fn unknown(line: &str) -> String {
+    format!("Unknown message {}!", line)
+}
+
+fn parse_argument<T: FromStr>(argument: &str, line: &str) -> Result<T, String> {
+    argument.parse().map_err(|_| unknown(line))
+}
+
+fn parse_rows(rows: &str, line: &str) -> Result<Vec<Vec<u32>>, String> {
+    rows.split('/')
+        .map(|row| {
+            let numbers = row
+                .split_whitespace()
+                .map(|number| parse_argument(number, line))
+                .collect::<Result<Vec<u32>, String>>()?;
+            if numbers.is_empty() {
+                return Err(unknown(line));
+            }
+            Ok(numbers)
+        })
+        .collect()
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::Board(rows) => {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|row| {
+                        let numbers: Vec<String> = row.iter().map(u32::to_string).collect();
+                        numbers.join(" ")
+                    })
+                    .collect();
+                write!(f, "BOARD {}", rows.join("/"))
+            }
+            ClientMessage::Ready => write!(f, "READY"),
+            ClientMessage::Claim(board_index) => write!(f, "CLAIM {}", board_index),
+            ClientMessage::Next => write!(f, "NEXT"),
+        }
+    }
+}
+
+impl FromStr for ClientMessage {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        match line.split_once(' ') {
+            Some(("BOARD", rows)) => parse_rows(rows, line).map(ClientMessage::Board),
+            Some(("CLAIM", board_index)) => parse_argument(board_index, line).map(ClientMessage::Claim),
+            None if line == "READY" => Ok(ClientMessage::Ready),
+            None if line == "NEXT" => Ok(ClientMessage::Next),
+            _ => Err(unknown(line)),
+        }
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Registered(board_index) => write!(f, "REGISTERED {}", board_index),
+            ServerMessage::Draw { draw_index, number } => write!(f, "DRAW {} {}", draw_index, number),
+            ServerMessage::Claimed(board_index) => write!(f, "CLAIMED {}", board_index),
+            ServerMessage::Win(win) => write!(
+                f,
+                "WIN {} {} {} {} {}",
+                win.board_index, win.draw_index, win.number, win.score, win.pattern
+            ),
+            ServerMessage::Error(message) => write!(f, "ERROR {}", message),
+            ServerMessage::End => write!(f, "END"),
+        }
+    }
+}
+
+impl FromStr for ServerMessage {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        match line.split_once(' ') {
+            Some(("REGISTERED", board_index)) => {
+                parse_argument(board_index, line).map(ServerMessage::Registered)
+            }
+            Some(("DRAW", arguments)) => match arguments.split_once(' ') {
+                Some((draw_index, number)) => Ok(ServerMessage::Draw {
+                    draw_index: parse_argument(draw_index, line)?,
+                    number: parse_argument(number, line)?,
+                }),
+                None => Err(unknown(line)),
+            },
+            Some(("CLAIMED", board_index)) => {
+                parse_argument(board_index, line).map(ServerMessage::Claimed)
+            }
+            Some(("WIN", arguments)) => match arguments.splitn(5, ' ').collect::<Vec<_>>()[..] {
+                [board_index, draw_index, number, score, pattern] => Ok(ServerMessage::Win(Win {
+                    board_index: parse_argument(board_index, line)?,
+                    draw_index: parse_argument(draw_index, line)?,
+                    number: parse_argument(number, line)?,
+                    score: parse_argument(score, line)?,
+                    pattern: parse_argument(pattern, line)?,
+                })),
+                _ => Err(unknown(line)),
+            },
+            Some(("ERROR", message)) => Ok(ServerMessage::Error(message.to_string())),
+            None if line == "END" => Ok(ServerMessage::End),
+            _ => Err(unknown(line)),
+        }
+    }
+}
+
+/// One line per message in either direction.
+pub(crate) struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    pub(crate) fn new(stream: TcpStream) -> io::Result<Connection> {
+        Ok(Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    pub(crate) fn send<M: fmt::Display>(&mut self, message: &M) -> io::Result<()> {
+        self.writer.write_all(format!("{}\n", message).as_bytes())
+    }
+
+    /// Reads the next message. A closed connection is an error, a malformed line is
+    /// handed back for the caller to answer.
+    pub(crate) fn receive<M: FromStr<Err = String>>(&mut self) -> io::Result<Result<M, String>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed!"));
+        }
+        Ok(line.trim_end().parse())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WinningPattern;
+    use test_case::test_case;
+
+    #[test_case(ClientMessage::Board(vec![vec![1, 2], vec![3, 4]]) => "BOARD 1 2/3 4")]
+    #[test_case(ClientMessage::Ready => "READY")]
+    #[test_case(ClientMessage::Claim(7) => "CLAIM 7")]
+    #[test_case(ClientMessage::Next => "NEXT")]
+    fn client_message_round_trips(message: ClientMessage) -> String {
+        let line = message.to_string();
+        assert_eq!(line.parse(), Ok(message));
+        line
+    }
+
+    #[test_case(ServerMessage::Registered(3) => "REGISTERED 3")]
+    #[test_case(ServerMessage::Draw { draw_index: 2, number: 17 } => "DRAW 2 17")]
+    #[test_case(ServerMessage::Claimed(3) => "CLAIMED 3")]
+    #[test_case(ServerMessage::Win(Win { board_index: 1, number: 24, draw_index: 11, pattern: WinningPattern::Row(0), score: 4512 }) => "WIN 1 11 24 4512 row 0")]
+    #[test_case(ServerMessage::Error("Board 2 has not won on this draw!".to_string()) => "ERROR Board 2 has not won on this draw!")]
+    #[test_case(ServerMessage::End => "END")]
+    fn server_message_round_trips(message: ServerMessage) -> String {
+        let line = message.to_string();
+        assert_eq!(line.parse(), Ok(message));
+        line
+    }
+
+    #[test_case("BOARD 1 2//3 4")]
+    #[test_case("BOARD 1 x")]
+    #[test_case("CLAIM")]
+    #[test_case("READY now")]
+    #[test_case("HELLO")]
+    fn client_message_rejects_malformed_line(line: &str) {
+        assert_eq!(line.parse::<ClientMessage>(), Err(format!("Unknown message {}!", line)));
+    }
+
+    #[test_case("DRAW 2")]
+    #[test_case("WIN 1 11 24 4512 the centre")]
+    #[test_case("REGISTERED -1")]
+    fn server_message_rejects_malformed_line(line: &str) {
+        assert_eq!(line.parse::<ServerMessage>(), Err(format!("Unknown message {}!", line)));
+    }
+}
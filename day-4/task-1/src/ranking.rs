@@ -0,0 +1,160 @@
+use crate::{Gameboard, Position, Win, WinRule, WinningPattern};
+use std::collections::HashMap;
+
+impl Gameboard {
+    fn draw_index_at(
+        &self,
+        position: &Position,
+        draw_indexes: &HashMap<u32, usize>,
+    ) -> Option<usize> {
+        let field = self.data.get(position.x)?.get(position.y)?;
+        draw_indexes.get(&field.value()).copied()
+    }
+
+    /// First draw completing one of the rule's patterns: a pattern completes at the
+    /// latest draw of its cells, and the board wins with the pattern completing first.
+    fn win_draw(
+        &self,
+        draw_indexes: &HashMap<u32, usize>,
+        rule: &WinRule,
+    ) -> Option<(usize, WinningPattern)> {
+        rule.patterns(self)
+            .into_iter()
+            .filter_map(|(pattern, cells)| {
+                let draws = cells
+                    .iter()
+                    .map(|cell| self.draw_index_at(cell, draw_indexes))
+                    .collect::<Option<Vec<usize>>>()?;
+                draws
+                    .into_iter()
+                    .max()
+                    .map(|draw_index| (draw_index, pattern))
+            })
+            .min_by_key(|&(draw_index, _)| draw_index)
+    }
+
+    fn sum_unmarked_after(&self, draw_indexes: &HashMap<u32, usize>, draw_index: usize) -> u32 {
+        self.data
+            .iter()
+            .flatten()
+            .map(|field| field.value())
+            .filter(|value| {
+                draw_indexes
+                    .get(value)
+                    .is_none_or(|&drawn| drawn > draw_index)
+            })
+            .sum()
+    }
+}
+
+/// Works out when every board would win if it played alone, without replaying the
+/// draws: each number's draw index is looked up once, and a board wins at the earliest
+/// draw completing one of its patterns. Returns the boards that win, ranked by draw and
+/// then by board, so the first and last entries answer both parts of the puzzle. Boards
+/// are taken as parsed, with nothing marked yet.
+pub fn rank_boards(lucky_numbers: &[u32], gameboards: &[Gameboard], rule: &WinRule) -> Vec<Win> {
+    let mut draw_indexes = HashMap::new();
+    for (draw_index, &lucky_number) in lucky_numbers.iter().enumerate() {
+        draw_indexes.entry(lucky_number).or_insert(draw_index);
+    }
+    let mut ranking: Vec<Win> = gameboards
+        .iter()
+        .enumerate()
+        .filter_map(|(board_index, gameboard)| {
+            let (draw_index, pattern) = gameboard.win_draw(&draw_indexes, rule)?;
+            let number = lucky_numbers[draw_index];
+            Some(Win {
+                board_index,
+                number,
+                draw_index,
+                pattern,
+                score: gameboard.sum_unmarked_after(&draw_indexes, draw_index) * number,
+            })
+        })
+        .collect();
+    ranking.sort_by_key(|win| (win.draw_index, win.board_index));
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_game, play, BoardSize, GeneratorOptions};
+    use test_case::test_case;
+
+    #[test]
+    fn rank_boards_ranks_by_win_draw() {
+        let gameboards = vec![
+            Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]]),
+            Gameboard::build(&mut vec![vec![5, 6], vec![7, 8]]),
+            Gameboard::build(&mut vec![vec![4, 9], vec![8, 10]]),
+        ];
+
+        let actual = rank_boards(&[8, 4, 6, 1, 2, 5], &gameboards, &WinRule::Lines);
+        let expected = vec![
+            Win {
+                board_index: 2,
+                number: 4,
+                draw_index: 1,
+                pattern: WinningPattern::Column(0),
+                score: 76,
+            },
+            Win {
+                board_index: 1,
+                number: 6,
+                draw_index: 2,
+                pattern: WinningPattern::Column(1),
+                score: 72,
+            },
+            Win {
+                board_index: 0,
+                number: 2,
+                draw_index: 4,
+                pattern: WinningPattern::Row(0),
+                score: 6,
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn rank_boards_leaves_out_boards_that_never_win() {
+        let gameboards = vec![
+            Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]]),
+            Gameboard::build(&mut vec![vec![5, 6], vec![7, 8]]),
+        ];
+
+        let actual = rank_boards(&[5, 1, 8, 2], &gameboards, &WinRule::Lines);
+        assert_eq!(actual.len(), 1);
+        assert_eq!(
+            (actual[0].board_index, actual[0].pattern),
+            (0, WinningPattern::Row(0))
+        );
+    }
+
+    #[test_case(WinRule::Lines)]
+    #[test_case(WinRule::Diagonals)]
+    #[test_case(WinRule::FourCorners)]
+    #[test_case(WinRule::Blackout)]
+    #[test_case("mask:11/11,00100/01110/00100".parse().unwrap())]
+    fn rank_boards_matches_play(rule: WinRule) {
+        for seed in 0..5 {
+            let options = GeneratorOptions {
+                boards: 50,
+                size: BoardSize {
+                    rows: 5,
+                    columns: 5,
+                },
+                range: 60,
+                seed,
+            };
+            let game = generate_game(&options).unwrap();
+            let gameboards = game.gameboards();
+
+            assert_eq!(
+                rank_boards(&game.lucky_numbers, &gameboards, &rule),
+                play(&game.lucky_numbers, gameboards, &rule)
+            );
+        }
+    }
+}
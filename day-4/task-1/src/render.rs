@@ -0,0 +1,190 @@
+use crate::{Field, Gameboard, Position, WinRule, WinningPattern};
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+const RESET: &str = "\x1b[0m";
+const MARKED: &str = "\x1b[1;32m";
+const WINNING: &str = "\x1b[1;30;43m";
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Draws boards for the terminal. Marked numbers are bold green and the winning line
+/// is highlighted; without colour they are wrapped in `[]` and `<>` instead.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Renderer {
+    pub colour: bool,
+    /// Pause after every draw when showing a game. The screen is cleared between draws.
+    pub delay: Option<Duration>,
+}
+
+impl Renderer {
+    fn cell(&self, value: u32, width: usize, marked: bool, winning: bool) -> String {
+        let number = format!("{:>width$}", value, width = width);
+        match (self.colour, winning, marked) {
+            (true, true, _) => format!("{} {} {}", WINNING, number, RESET),
+            (true, false, true) => format!("{} {} {}", MARKED, number, RESET),
+            (false, true, _) => format!("<{}>", number),
+            (false, false, true) => format!("[{}]", number),
+            (_, false, false) => format!(" {} ", number),
+        }
+    }
+
+    /// Renders `gameboard` as a grid, highlighting the cells of `pattern` if the board
+    /// has won with it.
+    pub fn render_board(
+        &self,
+        gameboard: &Gameboard,
+        pattern: Option<&WinningPattern>,
+        rule: &WinRule,
+    ) -> String {
+        let winning_cells: Vec<Position> = pattern
+            .and_then(|pattern| {
+                rule.patterns(gameboard)
+                    .into_iter()
+                    .find(|(candidate, _)| candidate == pattern)
+            })
+            .map_or(vec![], |(_, cells)| cells);
+        let width = gameboard.width();
+        let mut rendered = String::new();
+        for (x, row) in gameboard.data.iter().enumerate() {
+            let cells: String = row
+                .iter()
+                .enumerate()
+                .map(|(y, field)| {
+                    let winning = winning_cells.contains(&Position { x, y });
+                    match field {
+                        Field::Unmarked(value) => self.cell(*value, width, false, winning),
+                        Field::Marked(value) => self.cell(*value, width, true, winning),
+                    }
+                })
+                .collect();
+            rendered.push_str(cells.trim_end());
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    fn write_frame<W: Write>(
+        &self,
+        out: &mut W,
+        draw: (usize, u32),
+        gameboards: &[Gameboard],
+        patterns: &[Option<WinningPattern>],
+        rule: &WinRule,
+    ) -> io::Result<()> {
+        if self.delay.is_some() {
+            write!(out, "{}", CLEAR_SCREEN)?;
+        }
+        writeln!(out, "Draw {}: {}", draw.0, draw.1)?;
+        for (board_index, (gameboard, pattern)) in gameboards.iter().zip(patterns).enumerate() {
+            writeln!(out)?;
+            match pattern {
+                Some(pattern) => writeln!(out, "Board {}, won with {}", board_index, pattern)?,
+                None => writeln!(out, "Board {}", board_index)?,
+            }
+            write!(out, "{}", self.render_board(gameboard, pattern.as_ref(), rule))?;
+        }
+        out.flush()
+    }
+
+    /// Plays the game the same way as `play`, so boards stop being marked once they
+    /// have won. With a delay every draw is written as a frame, otherwise only the
+    /// boards as they stand after the last draw played.
+    pub fn show_game<W: Write>(
+        &self,
+        out: &mut W,
+        lucky_numbers: &[u32],
+        mut gameboards: Vec<Gameboard>,
+        rule: &WinRule,
+    ) -> io::Result<()> {
+        let mut patterns: Vec<Option<WinningPattern>> = vec![None; gameboards.len()];
+        let mut last_draw = None;
+        for (draw_index, &lucky_number) in lucky_numbers.iter().enumerate() {
+            for (gameboard, pattern) in gameboards.iter_mut().zip(patterns.iter_mut()) {
+                if pattern.is_none() {
+                    *pattern = gameboard.mark(lucky_number, rule);
+                }
+            }
+            last_draw = Some((draw_index, lucky_number));
+            if let Some(delay) = self.delay {
+                self.write_frame(out, (draw_index, lucky_number), &gameboards, &patterns, rule)?;
+                thread::sleep(delay);
+            }
+            if patterns.iter().all(Option::is_some) {
+                break;
+            }
+        }
+        match (self.delay, last_draw) {
+            (None, Some(draw)) => self.write_frame(out, draw, &gameboards, &patterns, rule),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gameboard() -> Gameboard {
+        Gameboard::from_data(vec![
+            vec![Field::Marked(1), Field::Marked(12), Field::Marked(3)],
+            vec![Field::Unmarked(4), Field::Marked(5), Field::Unmarked(6)],
+        ])
+    }
+
+    #[test]
+    fn render_board_highlights_winning_line() {
+        let renderer = Renderer::default();
+
+        let actual = renderer.render_board(&gameboard(), Some(&WinningPattern::Row(0)), &WinRule::Lines);
+        assert_eq!(actual, "< 1><12>< 3>\n  4 [ 5]  6\n");
+    }
+
+    #[test]
+    fn render_board_colours_marked_cells() {
+        let renderer = Renderer {
+            colour: true,
+            ..Default::default()
+        };
+
+        let actual = renderer.render_board(&gameboard(), None, &WinRule::Lines);
+        let expected = format!(
+            "{m}  1 {r}{m} 12 {r}{m}  3 {r}\n  4 {m}  5 {r}  6\n",
+            m = MARKED,
+            r = RESET
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn show_game_writes_boards_after_last_draw() {
+        let gameboards = vec![
+            Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]]),
+            Gameboard::build(&mut vec![vec![5, 6], vec![7, 8]]),
+        ];
+        let mut out = vec![];
+
+        Renderer::default()
+            .show_game(&mut out, &[2, 5, 4, 9, 1], gameboards, &WinRule::Lines)
+            .unwrap();
+        let expected = "Draw 4: 1\n\nBoard 0, won with column 1\n 1 <2>\n 3 <4>\n\nBoard 1\n[5] 6\n 7  8\n";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn show_game_writes_frame_per_draw_with_delay() {
+        let gameboards = vec![Gameboard::build(&mut vec![vec![1, 2]])];
+        let renderer = Renderer {
+            colour: false,
+            delay: Some(Duration::ZERO),
+        };
+        let mut out = vec![];
+
+        renderer
+            .show_game(&mut out, &[1, 2, 3], gameboards, &WinRule::Lines)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches(CLEAR_SCREEN).count(), 1);
+        assert!(out.ends_with("Draw 0: 1\n\nBoard 0, won with column 0\n<1> 2\n"));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::protocol::{ClientMessage, Connection, ServerMessage};
+use crate::{finish_gameboard, BoardOptions, BoardSize, Duplicates, Gameboard, Win, WinRule};
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerOptions {
+    pub players: usize,
+    pub rule: WinRule,
+    /// Boards have to be this size. Without it the first board registered sets the size.
+    pub size: Option<BoardSize>,
+    /// What to do with a number appearing twice on a registered board.
+    pub duplicates: Duplicates,
+    /// Announce every win without waiting for a claim.
+    pub auto_claim: bool,
+    /// How long to wait for a player to connect or send its next message before giving
+    /// up on the game. Without it a missing or silent player stalls the game forever.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        ServerOptions {
+            players: 1,
+            rule: WinRule::default(),
+            size: None,
+            duplicates: Duplicates::default(),
+            auto_claim: false,
+            timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// How often the listener is polled for a player while a timeout is set.
+const ACCEPT_POLL: Duration = Duration::from_millis(10);
+
+struct Board {
+    owner: usize,
+    gameboard: Gameboard,
+    /// Won, or completed without being claimed in time.
+    finished: bool,
+}
+
+/// Bingo over TCP, played in lockstep so every game is deterministic. Players connect
+/// and register boards until they send `READY`, one player after the other. Then for
+/// every draw the server sends `DRAW` to everyone, and each player claims its boards
+/// that completed on that draw and answers `NEXT`. A board completing without a claim
+/// on the same draw is out of the game. Once every player is done with the draw the
+/// wins are announced to everyone in board order, and `END` closes the game.
+pub struct Server {
+    listener: TcpListener,
+}
+
+impl Server {
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(address)?,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Plays one game with `options.players` players and returns the wins announced.
+    pub fn run(&self, lucky_numbers: &[u32], options: &ServerOptions) -> io::Result<Vec<Win>> {
+        let mut players = vec![];
+        let mut boards = vec![];
+        let mut size = options.size;
+        for owner in 0..options.players {
+            let stream = self.accept(options.timeout)?;
+            stream.set_read_timeout(options.timeout)?;
+            let mut player = Connection::new(stream)?;
+            register_boards(&mut player, owner, options, &mut boards, &mut size)?;
+            players.push(player);
+        }
+
+        let mut wins = vec![];
+        for (draw_index, &lucky_number) in lucky_numbers.iter().enumerate() {
+            if boards.iter().all(|board| board.finished) {
+                break;
+            }
+            broadcast(&mut players, &ServerMessage::Draw { draw_index, number: lucky_number })?;
+            let mut completed = mark_boards(&mut boards, draw_index, lucky_number, &options.rule);
+            let mut confirmed = vec![];
+            for (owner, player) in players.iter_mut().enumerate() {
+                take_claims(player, owner, &boards, &mut completed, &mut confirmed)?;
+            }
+            if options.auto_claim {
+                confirmed.extend(completed.iter_mut().filter_map(Option::take));
+            }
+            confirmed.sort_by_key(|win| win.board_index);
+            for win in confirmed {
+                broadcast(&mut players, &ServerMessage::Win(win.clone()))?;
+                wins.push(win);
+            }
+        }
+        broadcast(&mut players, &ServerMessage::End)?;
+        Ok(wins)
+    }
+
+    /// Waits for the next player, polling the listener so a player who never connects
+    /// times out like a silent one.
+    fn accept(&self, timeout: Option<Duration>) -> io::Result<TcpStream> {
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => {
+                self.listener.set_nonblocking(false)?;
+                return self.listener.accept().map(|(stream, _)| stream);
+            }
+        };
+        self.listener.set_nonblocking(true)?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false)?;
+                    return Ok(stream);
+                }
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    if left.is_zero() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "no player connected in time",
+                        ));
+                    }
+                    thread::sleep(ACCEPT_POLL.min(left));
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+fn broadcast(players: &mut [Connection], message: &ServerMessage) -> io::Result<()> {
+    for player in players {
+        player.send(message)?;
+    }
+    Ok(())
+}
+
+/// Checks a registered board like a board read from the input, numbering its rows
+/// from one. `size` is only set once the board is accepted.
+fn parse_board(rows: &[Vec<u32>], size: &mut Option<BoardSize>, options: &ServerOptions) -> Result<Gameboard, String> {
+    if rows.first().is_none_or(Vec::is_empty) {
+        return Err("Board has no numbers!".to_string());
+    }
+    let numbered_rows = rows.iter().cloned().enumerate().map(|(i, row)| (i + 1, row)).collect();
+    let board_options = BoardOptions {
+        duplicates: options.duplicates,
+        size: *size,
+    };
+    let mut board_size = *size;
+    let gameboard = finish_gameboard(0, numbered_rows, &mut board_size, &board_options)
+        .map_err(|error| error.to_string())?;
+    options.rule.check_size(gameboard.size())?;
+    *size = board_size;
+    Ok(gameboard)
+}
+
+fn register_boards(
+    player: &mut Connection,
+    owner: usize,
+    options: &ServerOptions,
+    boards: &mut Vec<Board>,
+    size: &mut Option<BoardSize>,
+) -> io::Result<()> {
+    loop {
+        let reply = match player.receive()? {
+            Ok(ClientMessage::Board(rows)) => match parse_board(&rows, size, options) {
+                Ok(gameboard) => {
+                    boards.push(Board {
+                        owner,
+                        gameboard,
+                        finished: false,
+                    });
+                    ServerMessage::Registered(boards.len() - 1)
+                }
+                Err(error) => ServerMessage::Error(error),
+            },
+            Ok(ClientMessage::Ready) => return Ok(()),
+            Ok(message) => ServerMessage::Error(format!("Unexpected message {}!", message)),
+            Err(error) => ServerMessage::Error(error),
+        };
+        player.send(&reply)?;
+    }
+}
+
+/// Marks `lucky_number` on the boards still playing and returns the wins it completes,
+/// indexed by board.
+fn mark_boards(boards: &mut [Board], draw_index: usize, lucky_number: u32, rule: &WinRule) -> Vec<Option<Win>> {
+    let mut completed = vec![None; boards.len()];
+    for (board_index, board) in boards.iter_mut().enumerate() {
+        if board.finished {
+            continue;
+        }
+        if let Some(pattern) = board.gameboard.mark(lucky_number, rule) {
+            board.finished = true;
+            completed[board_index] = Some(Win {
+                board_index,
+                number: lucky_number,
+                draw_index,
+                pattern,
+                score: board.gameboard.sum_unmarked() * lucky_number,
+            });
+        }
+    }
+    completed
+}
+
+fn take_claims(
+    player: &mut Connection,
+    owner: usize,
+    boards: &[Board],
+    completed: &mut [Option<Win>],
+    confirmed: &mut Vec<Win>,
+) -> io::Result<()> {
+    loop {
+        let reply = match player.receive()? {
+            Ok(ClientMessage::Claim(board_index)) => match boards.get(board_index) {
+                Some(board) if board.owner != owner => {
+                    ServerMessage::Error(format!("Board {} is not yours!", board_index))
+                }
+                Some(_) => match completed[board_index].take() {
+                    Some(win) => {
+                        confirmed.push(win);
+                        ServerMessage::Claimed(board_index)
+                    }
+                    None => ServerMessage::Error(format!(
+                        "Board {} has not won on this draw!",
+                        board_index
+                    )),
+                },
+                None => ServerMessage::Error(format!("Unknown board {}!", board_index)),
+            },
+            Ok(ClientMessage::Next) => return Ok(()),
+            Ok(message) => ServerMessage::Error(format!("Unexpected message {}!", message)),
+            Err(error) => ServerMessage::Error(error),
+        };
+        player.send(&reply)?;
+    }
+}
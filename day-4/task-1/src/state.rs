@@ -0,0 +1,262 @@
+use crate::{index_boards, Gameboard, Win, WinRule};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A game that can be stopped after any draw and picked up again, for example from a
+/// JSON checkpoint. Boards keep their marks, and boards that have won stop playing.
+/// Loading a checkpoint checks that its boards, rule and wins fit together.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Checkpoint", into = "Checkpoint")]
+pub struct GameState {
+    pub rule: WinRule,
+    /// Draws played so far, so wins after resuming still count draws from the start.
+    pub draws_played: usize,
+    pub remaining_draws: Vec<u32>,
+    pub gameboards: Vec<Gameboard>,
+    pub wins: Vec<Win>,
+    /// Boards holding each number, built once when the game is created or loaded.
+    boards_by_number: HashMap<u32, Vec<usize>>,
+    has_won: Vec<bool>,
+}
+
+/// What a saved `GameState` holds, everything else is rebuilt on load.
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    rule: WinRule,
+    draws_played: usize,
+    remaining_draws: Vec<u32>,
+    gameboards: Vec<Gameboard>,
+    wins: Vec<Win>,
+}
+
+impl From<GameState> for Checkpoint {
+    fn from(state: GameState) -> Self {
+        Checkpoint {
+            rule: state.rule,
+            draws_played: state.draws_played,
+            remaining_draws: state.remaining_draws,
+            gameboards: state.gameboards,
+            wins: state.wins,
+        }
+    }
+}
+
+impl TryFrom<Checkpoint> for GameState {
+    type Error = String;
+
+    fn try_from(checkpoint: Checkpoint) -> Result<Self, Self::Error> {
+        if let Some(first) = checkpoint.gameboards.first() {
+            let size = first.size();
+            for (board_index, gameboard) in checkpoint.gameboards.iter().enumerate() {
+                if gameboard.size() != size {
+                    return Err(format!(
+                        "Board {} is {} but board 0 is {}!",
+                        board_index,
+                        gameboard.size(),
+                        size
+                    ));
+                }
+            }
+        }
+        checkpoint.rule.check_boards(&checkpoint.gameboards)?;
+        let mut has_won = vec![false; checkpoint.gameboards.len()];
+        for win in &checkpoint.wins {
+            match has_won.get_mut(win.board_index) {
+                Some(won) if !*won => *won = true,
+                Some(_) => return Err(format!("Board {} won twice!", win.board_index)),
+                None => return Err(format!("Win on unknown board {}!", win.board_index)),
+            }
+        }
+
+        Ok(GameState {
+            boards_by_number: index_boards(&checkpoint.gameboards),
+            has_won,
+            rule: checkpoint.rule,
+            draws_played: checkpoint.draws_played,
+            remaining_draws: checkpoint.remaining_draws,
+            gameboards: checkpoint.gameboards,
+            wins: checkpoint.wins,
+        })
+    }
+}
+
+impl GameState {
+    pub fn new(lucky_numbers: Vec<u32>, gameboards: Vec<Gameboard>, rule: WinRule) -> GameState {
+        GameState {
+            rule,
+            draws_played: 0,
+            remaining_draws: lucky_numbers,
+            boards_by_number: index_boards(&gameboards),
+            has_won: vec![false; gameboards.len()],
+            gameboards,
+            wins: vec![],
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining_draws.is_empty() || self.wins.len() == self.gameboards.len()
+    }
+
+    /// Plays up to `draws` more numbers, stopping early once every board has won, and
+    /// returns the wins they produced. Each draw only visits the boards holding the
+    /// number.
+    pub fn advance(&mut self, draws: usize) -> &[Win] {
+        let first_new_win = self.wins.len();
+        let mut played = 0;
+        for &lucky_number in self.remaining_draws.iter().take(draws) {
+            if self.wins.len() == self.gameboards.len() {
+                break;
+            }
+            let draw_index = self.draws_played + played;
+            played += 1;
+            let board_indexes = self
+                .boards_by_number
+                .get(&lucky_number)
+                .map_or(&[][..], Vec::as_slice);
+            for &board_index in board_indexes {
+                if self.has_won[board_index] {
+                    continue;
+                }
+                let gameboard = &mut self.gameboards[board_index];
+                if let Some(pattern) = gameboard.mark(lucky_number, &self.rule) {
+                    self.has_won[board_index] = true;
+                    self.wins.push(Win {
+                        board_index,
+                        number: lucky_number,
+                        draw_index,
+                        pattern,
+                        score: gameboard.sum_unmarked() * lucky_number,
+                    });
+                }
+            }
+        }
+        self.remaining_draws.drain(..played);
+        self.draws_played += played;
+        &self.wins[first_new_win..]
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<GameState> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_game, play, BoardSize, GeneratorOptions, Position, WinningPattern};
+    use test_case::test_case;
+
+    fn game() -> (Vec<u32>, Vec<Gameboard>) {
+        let options = GeneratorOptions {
+            boards: 30,
+            size: BoardSize { rows: 4, columns: 4 },
+            range: 50,
+            seed: 11,
+        };
+        let game = generate_game(&options).unwrap();
+        (game.lucky_numbers.clone(), game.gameboards())
+    }
+
+    #[test]
+    fn gameboard_serializes_fields_only() {
+        let mut gameboard = Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]]);
+        gameboard.check_number(2);
+
+        let json = serde_json::to_string(&gameboard).unwrap();
+        assert_eq!(json, r#"[[{"Unmarked":1},{"Marked":2}],[{"Unmarked":3},{"Unmarked":4}]]"#);
+        assert_eq!(serde_json::from_str::<Gameboard>(&json).unwrap(), gameboard);
+    }
+
+    #[test]
+    fn game_state_round_trips_through_json() {
+        let (lucky_numbers, gameboards) = game();
+        let rule = WinRule::Masks(vec![vec![Position { x: 1, y: 2 }]]);
+        let mut state = GameState::new(lucky_numbers, gameboards, rule);
+        state.advance(20);
+
+        let resumed = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        assert_eq!(resumed, state);
+    }
+
+    #[test_case(1)]
+    #[test_case(7)]
+    #[test_case(1000)]
+    fn game_state_resumed_from_checkpoints_matches_play(draws: usize) {
+        let (lucky_numbers, gameboards) = game();
+        let expected = play(&lucky_numbers, gameboards.clone(), &WinRule::Lines);
+
+        let mut state = GameState::new(lucky_numbers, gameboards, WinRule::Lines);
+        while !state.is_finished() {
+            state.advance(draws);
+            state = GameState::from_json(&state.to_json().unwrap()).unwrap();
+        }
+        assert_eq!(state.wins, expected);
+    }
+
+    #[test]
+    fn advance_returns_new_wins() {
+        let gameboards = vec![
+            Gameboard::build(&mut vec![vec![1, 2], vec![3, 4]]),
+            Gameboard::build(&mut vec![vec![5, 6], vec![7, 8]]),
+        ];
+        let mut state = GameState::new(vec![1, 2, 5, 7, 3], gameboards, WinRule::Lines);
+
+        assert_eq!(state.advance(2).len(), 1);
+        let wins = state.advance(2);
+        assert_eq!(wins.len(), 1);
+        assert_eq!((wins[0].board_index, wins[0].draw_index, wins[0].pattern), (1, 3, WinningPattern::Column(0)));
+        assert_eq!((state.draws_played, state.remaining_draws.clone()), (4, vec![3]));
+        assert!(state.is_finished());
+    }
+
+    #[test]
+    fn from_json_rejects_malformed_state() {
+        assert!(GameState::from_json(r#"{"rule": "Lines"}"#).is_err());
+    }
+
+    const BOARD: &str = r#"[[{"Marked":1},{"Marked":2}],[{"Unmarked":3},{"Unmarked":4}]]"#;
+    const WIDE_BOARD: &str = r#"[[{"Unmarked":1},{"Unmarked":2},{"Unmarked":5}]]"#;
+    const RAGGED_BOARD: &str = r#"[[{"Unmarked":1},{"Unmarked":2}],[{"Unmarked":3}]]"#;
+
+    fn checkpoint(rule: &str, gameboards: &[&str], wins: &[usize]) -> String {
+        let wins: Vec<String> = wins
+            .iter()
+            .map(|board_index| {
+                format!(
+                    r#"{{"board_index":{},"number":2,"draw_index":1,"pattern":{{"Row":0}},"score":14}}"#,
+                    board_index
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"rule":{},"draws_played":2,"remaining_draws":[3],"gameboards":[{}],"wins":[{}]}}"#,
+            rule,
+            gameboards.join(","),
+            wins.join(",")
+        )
+    }
+
+    #[test_case(r#""Lines""#, &[BOARD, BOARD], &[1, 2] => "Win on unknown board 2!")]
+    #[test_case(r#""Lines""#, &[BOARD, BOARD], &[0, 0] => "Board 0 won twice!")]
+    #[test_case(r#""Lines""#, &[BOARD, WIDE_BOARD], &[] => "Board 1 is 1x3 but board 0 is 2x2!")]
+    #[test_case(r#""Lines""#, &[BOARD, RAGGED_BOARD], &[] => "Board rows are empty or differ in length!")]
+    #[test_case(r#""Lines""#, &["[]"], &[] => "Board rows are empty or differ in length!")]
+    #[test_case(r#"{"Masks":[[{"x":0,"y":2}]]}"#, &[BOARD], &[] => "Mask 0 has cell 0,2 outside a 2x2 board!")]
+    fn from_json_rejects_inconsistent_checkpoint(rule: &str, gameboards: &[&str], wins: &[usize]) -> String {
+        let error = GameState::from_json(&checkpoint(rule, gameboards, wins)).unwrap_err();
+        let error = error.to_string();
+        error.split(" at line").next().unwrap().to_string()
+    }
+
+    #[test]
+    fn from_json_accepts_consistent_checkpoint() {
+        let state = GameState::from_json(&checkpoint(r#""Lines""#, &[BOARD, BOARD], &[0, 1])).unwrap();
+
+        assert_eq!(state.wins.len(), 2);
+        assert!(state.is_finished());
+    }
+}
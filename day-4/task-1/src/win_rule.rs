@@ -0,0 +1,420 @@
+use crate::{BoardSize, Field, Gameboard, Position};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which cells a board has to mark to win.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub enum WinRule {
+    /// Any full row or column.
+    #[default]
+    Lines,
+    /// Any full row, column or diagonal of a square board.
+    Diagonals,
+    FourCorners,
+    /// Every cell of the board.
+    Blackout,
+    /// Any of the given sets of cells.
+    Masks(Vec<Vec<Position>>),
+}
+
+/// Parses a mask written row by row, rows separated by `/` and marked cells as `1`,
+/// e.g. `101/010/101`.
+fn parse_mask(mask: &str) -> Result<Vec<Position>, String> {
+    let mut cells = vec![];
+    for (x, row) in mask.split('/').enumerate() {
+        for (y, cell) in row.chars().enumerate() {
+            match cell {
+                '1' => cells.push(Position { x, y }),
+                '0' => {}
+                _ => return Err(format!("Unknown mask cell {}!", cell)),
+            }
+        }
+    }
+    if cells.is_empty() {
+        return Err(format!("Mask {} has no cells!", mask));
+    }
+    Ok(cells)
+}
+
+impl FromStr for WinRule {
+    type Err = String;
+
+    /// Accepts `lines`, `diagonals`, `corners`, `blackout` or `mask:` followed by
+    /// comma separated masks.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "lines" => Ok(WinRule::Lines),
+            "diagonals" => Ok(WinRule::Diagonals),
+            "corners" => Ok(WinRule::FourCorners),
+            "blackout" => Ok(WinRule::Blackout),
+            _ => match name.strip_prefix("mask:") {
+                Some(masks) => masks
+                    .split(',')
+                    .map(parse_mask)
+                    .collect::<Result<_, _>>()
+                    .map(WinRule::Masks),
+                None => Err(format!("Unknown win rule {}!", name)),
+            },
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum WinningPattern {
+    Row(usize),
+    Column(usize),
+    Diagonal,
+    AntiDiagonal,
+    FourCorners,
+    Blackout,
+    /// Index of the completed mask.
+    Mask(usize),
+}
+
+impl fmt::Display for WinningPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WinningPattern::Row(index) => write!(f, "row {}", index),
+            WinningPattern::Column(index) => write!(f, "column {}", index),
+            WinningPattern::Diagonal => write!(f, "the diagonal"),
+            WinningPattern::AntiDiagonal => write!(f, "the anti-diagonal"),
+            WinningPattern::FourCorners => write!(f, "the four corners"),
+            WinningPattern::Blackout => write!(f, "the whole board"),
+            WinningPattern::Mask(index) => write!(f, "mask {}", index),
+        }
+    }
+}
+
+impl FromStr for WinningPattern {
+    type Err = String;
+
+    /// Reads back what `Display` writes.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let index = |index: &str| {
+            index
+                .parse()
+                .map_err(|_| format!("Unknown winning pattern {}!", name))
+        };
+        match name {
+            "the diagonal" => Ok(WinningPattern::Diagonal),
+            "the anti-diagonal" => Ok(WinningPattern::AntiDiagonal),
+            "the four corners" => Ok(WinningPattern::FourCorners),
+            "the whole board" => Ok(WinningPattern::Blackout),
+            _ => match name.split_once(' ') {
+                Some(("row", row)) => index(row).map(WinningPattern::Row),
+                Some(("column", column)) => index(column).map(WinningPattern::Column),
+                Some(("mask", mask)) => index(mask).map(WinningPattern::Mask),
+                _ => Err(format!("Unknown winning pattern {}!", name)),
+            },
+        }
+    }
+}
+
+impl WinRule {
+    /// Checks that every cell of every mask lies on a board of `size`. A mask reaching
+    /// past the board could never be completed.
+    pub fn check_size(&self, size: BoardSize) -> Result<(), String> {
+        if let WinRule::Masks(masks) = self {
+            for (index, mask) in masks.iter().enumerate() {
+                if let Some(cell) = mask
+                    .iter()
+                    .find(|cell| cell.x >= size.rows || cell.y >= size.columns)
+                {
+                    return Err(format!(
+                        "Mask {} has cell {},{} outside a {} board!",
+                        index, cell.x, cell.y, size
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the rule against the size of every board, see `check_size`.
+    pub fn check_boards(&self, gameboards: &[Gameboard]) -> Result<(), String> {
+        gameboards
+            .iter()
+            .try_for_each(|gameboard| self.check_size(gameboard.size()))
+    }
+
+    /// Every pattern the rule accepts on `gameboard` with the cells it covers, in the
+    /// order `winning_pattern` checks them.
+    pub(crate) fn patterns(&self, gameboard: &Gameboard) -> Vec<(WinningPattern, Vec<Position>)> {
+        let rows = gameboard.data.len();
+        let columns = gameboard.data.first().map_or(0, |row| row.len());
+        let lines = || {
+            let row_patterns = (0..rows).map(|x| {
+                let cells = (0..columns).map(|y| Position { x, y }).collect();
+                (WinningPattern::Row(x), cells)
+            });
+            let column_patterns = (0..columns).map(|y| {
+                let cells = (0..rows).map(|x| Position { x, y }).collect();
+                (WinningPattern::Column(y), cells)
+            });
+            row_patterns.chain(column_patterns).collect::<Vec<_>>()
+        };
+        match self {
+            WinRule::Lines => lines(),
+            WinRule::Diagonals => {
+                let mut patterns = lines();
+                if gameboard.is_square() {
+                    let diagonal = (0..rows).map(|i| Position { x: i, y: i }).collect();
+                    let anti_diagonal = (0..rows)
+                        .map(|i| Position {
+                            x: i,
+                            y: rows - 1 - i,
+                        })
+                        .collect();
+                    patterns.push((WinningPattern::Diagonal, diagonal));
+                    patterns.push((WinningPattern::AntiDiagonal, anti_diagonal));
+                }
+                patterns
+            }
+            WinRule::FourCorners => vec![(WinningPattern::FourCorners, gameboard.corners())],
+            WinRule::Blackout => {
+                let cells = (0..rows)
+                    .flat_map(|x| (0..columns).map(move |y| Position { x, y }))
+                    .collect();
+                vec![(WinningPattern::Blackout, cells)]
+            }
+            WinRule::Masks(masks) => masks
+                .iter()
+                .enumerate()
+                .map(|(index, mask)| (WinningPattern::Mask(index), mask.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Gameboard {
+    fn is_marked(&self, position: &Position) -> bool {
+        matches!(
+            self.data
+                .get(position.x)
+                .and_then(|row| row.get(position.y)),
+            Some(Field::Marked(_))
+        )
+    }
+
+    fn is_square(&self) -> bool {
+        self.data.iter().all(|row| row.len() == self.data.len())
+    }
+
+    fn is_diagonal_all_marked(&self, anti: bool) -> bool {
+        let size = self.data.len();
+        self.is_square()
+            && (0..size).all(|i| {
+                let y = if anti { size - 1 - i } else { i };
+                self.is_marked(&Position { x: i, y })
+            })
+    }
+
+    fn corners(&self) -> Vec<Position> {
+        let last_row = self.data.len().saturating_sub(1);
+        let last_column = self
+            .data
+            .first()
+            .map_or(0, |row| row.len().saturating_sub(1));
+        vec![
+            Position { x: 0, y: 0 },
+            Position {
+                x: 0,
+                y: last_column,
+            },
+            Position { x: last_row, y: 0 },
+            Position {
+                x: last_row,
+                y: last_column,
+            },
+        ]
+    }
+
+    /// Returns the pattern of the rule completed by marking `position`, if any.
+    pub(crate) fn winning_pattern(
+        &self,
+        position: &Position,
+        rule: &WinRule,
+    ) -> Option<WinningPattern> {
+        let lines = || {
+            if self.is_row_all_marked(position.x) {
+                Some(WinningPattern::Row(position.x))
+            } else if self.is_column_all_marked(position.y) {
+                Some(WinningPattern::Column(position.y))
+            } else {
+                None
+            }
+        };
+        match rule {
+            WinRule::Lines => lines(),
+            WinRule::Diagonals => lines().or_else(|| {
+                let size = self.data.len();
+                if position.x == position.y && self.is_diagonal_all_marked(false) {
+                    Some(WinningPattern::Diagonal)
+                } else if position.x + position.y + 1 == size && self.is_diagonal_all_marked(true) {
+                    Some(WinningPattern::AntiDiagonal)
+                } else {
+                    None
+                }
+            }),
+            WinRule::FourCorners => {
+                let corners = self.corners();
+                if corners.contains(position) && corners.iter().all(|corner| self.is_marked(corner))
+                {
+                    Some(WinningPattern::FourCorners)
+                } else {
+                    None
+                }
+            }
+            WinRule::Blackout => {
+                if self.unmarked_in_rows.iter().all(|&unmarked| unmarked == 0) {
+                    Some(WinningPattern::Blackout)
+                } else {
+                    None
+                }
+            }
+            WinRule::Masks(masks) => masks
+                .iter()
+                .position(|mask| {
+                    mask.contains(position) && mask.iter().all(|cell| self.is_marked(cell))
+                })
+                .map(WinningPattern::Mask),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::play;
+    use test_case::test_case;
+
+    fn board(values: &[&[u32]], marked: &[u32]) -> Gameboard {
+        Gameboard::from_data(
+            values
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&value| {
+                            if marked.contains(&value) {
+                                Field::Marked(value)
+                            } else {
+                                Field::Unmarked(value)
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    const SQUARE: &[&[u32]] = &[&[1, 2, 3], &[4, 5, 6], &[7, 8, 9]];
+
+    #[test_case(WinRule::Lines, &[1, 5, 9], 9 => None)]
+    #[test_case(WinRule::Lines, &[4, 5, 6], 5 => Some(WinningPattern::Row(1)))]
+    #[test_case(WinRule::Diagonals, &[3, 6, 9], 9 => Some(WinningPattern::Column(2)))]
+    #[test_case(WinRule::Diagonals, &[1, 5, 9], 9 => Some(WinningPattern::Diagonal))]
+    #[test_case(WinRule::Diagonals, &[3, 5, 7], 5 => Some(WinningPattern::AntiDiagonal))]
+    #[test_case(WinRule::FourCorners, &[1, 3, 7, 9], 7 => Some(WinningPattern::FourCorners))]
+    #[test_case(WinRule::FourCorners, &[1, 2, 3], 3 => None)]
+    #[test_case(WinRule::Blackout, &[1, 2, 3, 4, 5, 6, 7, 8], 8 => None)]
+    #[test_case(WinRule::Blackout, &[1, 2, 3, 4, 5, 6, 7, 8, 9], 5 => Some(WinningPattern::Blackout))]
+    #[test_case("mask:111/000/000,010/111/010".parse().unwrap(), &[2, 4, 5, 6, 8], 8 => Some(WinningPattern::Mask(1)))]
+    #[test_case("mask:010/111/010".parse().unwrap(), &[2, 4, 5, 6, 8], 1 => None)]
+    fn winning_pattern_follows_rule(
+        rule: WinRule,
+        marked: &[u32],
+        last: u32,
+    ) -> Option<WinningPattern> {
+        let gameboard = board(SQUARE, marked);
+        let position = Position {
+            x: (last as usize - 1) / 3,
+            y: (last as usize - 1) % 3,
+        };
+
+        gameboard.winning_pattern(&position, &rule)
+    }
+
+    #[test]
+    fn winning_pattern_ignores_diagonals_of_rectangular_board() {
+        let gameboard = board(&[&[1, 2, 3], &[4, 5, 6]], &[1, 5]);
+
+        assert_eq!(
+            gameboard.winning_pattern(&Position { x: 1, y: 1 }, &WinRule::Diagonals),
+            None
+        );
+    }
+
+    #[test_case("lines" => Ok(WinRule::Lines))]
+    #[test_case("corners" => Ok(WinRule::FourCorners))]
+    #[test_case("mask:10/01" => Ok(WinRule::Masks(vec![vec![Position { x: 0, y: 0 }, Position { x: 1, y: 1 }]])))]
+    #[test_case("mask:12" => Err("Unknown mask cell 2!".to_string()))]
+    #[test_case("mask:00" => Err("Mask 00 has no cells!".to_string()))]
+    #[test_case("zigzag" => Err("Unknown win rule zigzag!".to_string()))]
+    fn win_rule_from_str(name: &str) -> Result<WinRule, String> {
+        name.parse()
+    }
+
+    #[test_case("lines", 1, 1 => Ok(()))]
+    #[test_case("mask:111/010", 2, 3 => Ok(()))]
+    #[test_case("mask:1/01,001", 3, 2 => Err("Mask 1 has cell 0,2 outside a 3x2 board!".to_string()))]
+    #[test_case("mask:000/000/001", 2, 5 => Err("Mask 0 has cell 2,2 outside a 2x5 board!".to_string()))]
+    fn win_rule_check_size(rule: &str, rows: usize, columns: usize) -> Result<(), String> {
+        let rule: WinRule = rule.parse().unwrap();
+        rule.check_size(BoardSize { rows, columns })
+    }
+
+    #[test]
+    fn win_rule_check_boards_checks_every_board() {
+        let rule: WinRule = "mask:001".parse().unwrap();
+
+        assert_eq!(rule.check_boards(&[board(SQUARE, &[])]), Ok(()));
+        assert_eq!(
+            rule.check_boards(&[board(SQUARE, &[]), board(&[&[1, 2], &[3, 4]], &[])]),
+            Err("Mask 0 has cell 0,2 outside a 2x2 board!".to_string())
+        );
+    }
+
+    #[test_case(WinningPattern::Row(4))]
+    #[test_case(WinningPattern::Column(0))]
+    #[test_case(WinningPattern::Diagonal)]
+    #[test_case(WinningPattern::AntiDiagonal)]
+    #[test_case(WinningPattern::FourCorners)]
+    #[test_case(WinningPattern::Blackout)]
+    #[test_case(WinningPattern::Mask(2))]
+    fn winning_pattern_from_str_reads_display(pattern: WinningPattern) {
+        assert_eq!(pattern.to_string().parse(), Ok(pattern));
+    }
+
+    #[test_case("row" => Err("Unknown winning pattern row!".to_string()))]
+    #[test_case("row x" => Err("Unknown winning pattern row x!".to_string()))]
+    #[test_case("the centre" => Err("Unknown winning pattern the centre!".to_string()))]
+    fn winning_pattern_from_str_rejects_unknown(name: &str) -> Result<WinningPattern, String> {
+        name.parse()
+    }
+
+    #[test]
+    fn play_reports_winning_pattern() {
+        let gameboards = vec![
+            board(SQUARE, &[]),
+            board(&[&[9, 8, 7], &[6, 5, 4], &[3, 2, 1]], &[]),
+        ];
+
+        let actual = play(&[1, 2, 3, 5, 7, 9], gameboards, &WinRule::FourCorners);
+        let patterns: Vec<(usize, usize, WinningPattern)> = actual
+            .iter()
+            .map(|win| (win.board_index, win.draw_index, win.pattern))
+            .collect();
+        assert_eq!(
+            patterns,
+            vec![
+                (0, 5, WinningPattern::FourCorners),
+                (1, 5, WinningPattern::FourCorners)
+            ]
+        );
+        assert_eq!(
+            actual[0].to_string(),
+            "draw 5: 9 completes the four corners on board 0, score 162"
+        );
+    }
+}
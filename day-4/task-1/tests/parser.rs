@@ -0,0 +1,33 @@
+use task_1::{parse_game, play, BoardOptions, WinRule};
+use test_case::test_case;
+
+const EXAMPLE: &str = include_str!("inputs/example.txt");
+
+#[test_case(include_str!("inputs/no_trailing_newline.txt") ; "no trailing newline")]
+#[test_case(include_str!("inputs/crlf.txt") ; "crlf line endings")]
+#[test_case(include_str!("inputs/tabs.txt") ; "tab separated numbers")]
+#[test_case(include_str!("inputs/multiple_blank_lines.txt") ; "multiple blank lines")]
+#[test_case(include_str!("inputs/leading_whitespace.txt") ; "leading whitespace")]
+#[test_case(include_str!("inputs/whitespace_only_separators.txt") ; "whitespace only separators")]
+#[test_case(include_str!("inputs/mixed.txt") ; "mixed")]
+fn parse_game_reads_awkward_input_like_example(input: &str) {
+    let options = BoardOptions::default();
+    let expected = parse_game(&mut EXAMPLE.as_bytes(), &options).unwrap();
+
+    let (lucky_numbers, gameboards) = parse_game(&mut input.as_bytes(), &options).unwrap();
+    assert_eq!(lucky_numbers, expected.0);
+    assert_eq!(gameboards, expected.1);
+
+    let wins = play(&lucky_numbers, gameboards, &WinRule::Lines);
+    assert_eq!(wins.first().map(|win| win.score), Some(4512));
+    assert_eq!(wins.last().map(|win| win.score), Some(1924));
+}
+
+#[test]
+fn parse_game_reads_example() {
+    let (lucky_numbers, gameboards) =
+        parse_game(&mut EXAMPLE.as_bytes(), &BoardOptions::default()).unwrap();
+
+    assert_eq!(lucky_numbers.len(), 27);
+    assert_eq!(gameboards.len(), 3);
+}
@@ -0,0 +1,192 @@
+use std::io;
+use std::net::SocketAddr;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use task_1::{
+    generate_game, play, BoardSize, Client, ClientMessage, Duplicates, GeneratedGame,
+    GeneratorOptions, Server, ServerMessage, ServerOptions, Win, WinRule,
+};
+
+fn serve(lucky_numbers: Vec<u32>, options: ServerOptions) -> (SocketAddr, JoinHandle<io::Result<Vec<Win>>>) {
+    let server = Server::bind("127.0.0.1:0").unwrap();
+    let address = server.local_addr().unwrap();
+    let handle = thread::spawn(move || server.run(&lucky_numbers, &options));
+    (address, handle)
+}
+
+fn game() -> GeneratedGame {
+    let options = GeneratorOptions {
+        boards: 10,
+        size: BoardSize { rows: 5, columns: 5 },
+        range: 60,
+        seed: 3,
+    };
+    generate_game(&options).unwrap()
+}
+
+#[test]
+fn players_claiming_their_wins_match_play() {
+    let game = game();
+    let expected = play(&game.lucky_numbers, game.gameboards(), &WinRule::Lines);
+    let options = ServerOptions {
+        players: 2,
+        ..Default::default()
+    };
+    let (address, server) = serve(game.lucky_numbers.clone(), options);
+
+    // Connecting from this thread fixes the order the server takes the players in.
+    let first = Client::connect(address).unwrap();
+    let second = Client::connect(address).unwrap();
+    let (first_boards, second_boards) = game.boards.split_at(4);
+    let first_boards = first_boards.to_vec();
+    let second_boards = second_boards.to_vec();
+    let first = thread::spawn(move || first.play(&first_boards, &WinRule::Lines));
+    let second = thread::spawn(move || second.play(&second_boards, &WinRule::Lines));
+
+    assert_eq!(first.join().unwrap().unwrap(), expected);
+    assert_eq!(second.join().unwrap().unwrap(), expected);
+    assert_eq!(server.join().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn server_announces_wins_itself_with_auto_claim() {
+    let game = game();
+    let expected = play(&game.lucky_numbers, game.gameboards(), &WinRule::Lines);
+    let options = ServerOptions {
+        auto_claim: true,
+        ..Default::default()
+    };
+    let (address, server) = serve(game.lucky_numbers.clone(), options);
+
+    let mut client = Client::connect(address).unwrap();
+    for board in &game.boards {
+        client.register(board).unwrap();
+    }
+    client.send(&ClientMessage::Ready).unwrap();
+    let mut wins = vec![];
+    loop {
+        match client.receive().unwrap() {
+            ServerMessage::Draw { .. } => client.send(&ClientMessage::Next).unwrap(),
+            ServerMessage::Win(win) => wins.push(win),
+            ServerMessage::End => break,
+            message => panic!("Unexpected message {}!", message),
+        }
+    }
+
+    assert_eq!(wins, expected);
+    assert_eq!(server.join().unwrap().unwrap(), expected);
+}
+
+#[test]
+fn server_rejects_bad_boards_and_claims() {
+    let options = ServerOptions {
+        players: 2,
+        ..Default::default()
+    };
+    let (address, server) = serve(vec![9, 1, 3, 4], options);
+    let mut first = Client::connect(address).unwrap();
+    let mut second = Client::connect(address).unwrap();
+
+    assert_eq!(first.register(&[vec![1, 2], vec![3, 4]]).unwrap(), 0);
+    let error = first.register(&[vec![5, 6, 7], vec![8]]).unwrap_err();
+    assert_eq!(error.to_string(), "board 0, line 2: expected 3 numbers but found 1");
+    let error = first.register(&[vec![5, 6, 7]]).unwrap_err();
+    assert_eq!(error.to_string(), "board 0, line 1: expected a 2x2 board but found 1x3");
+    first.send(&ClientMessage::Ready).unwrap();
+    assert_eq!(second.register(&[vec![5, 6], vec![7, 8]]).unwrap(), 1);
+    second.send(&ClientMessage::Ready).unwrap();
+
+    let draw = ServerMessage::Draw { draw_index: 0, number: 9 };
+    assert_eq!(first.receive().unwrap(), draw);
+    assert_eq!(
+        first.request(&ClientMessage::Claim(0)).unwrap(),
+        ServerMessage::Error("Board 0 has not won on this draw!".to_string())
+    );
+    assert_eq!(
+        first.request(&ClientMessage::Claim(5)).unwrap(),
+        ServerMessage::Error("Unknown board 5!".to_string())
+    );
+    assert_eq!(
+        first.request(&ClientMessage::Ready).unwrap(),
+        ServerMessage::Error("Unexpected message READY!".to_string())
+    );
+    first.send(&ClientMessage::Next).unwrap();
+    assert_eq!(second.receive().unwrap(), draw);
+    assert_eq!(
+        second.request(&ClientMessage::Claim(0)).unwrap(),
+        ServerMessage::Error("Board 0 is not yours!".to_string())
+    );
+    second.send(&ClientMessage::Next).unwrap();
+
+    // Board 0 completes its first column on draw 2 but is only claimed on draw 3, too late.
+    for (draw_index, number) in [(1, 1), (2, 3)] {
+        for client in [&mut first, &mut second] {
+            assert_eq!(client.receive().unwrap(), ServerMessage::Draw { draw_index, number });
+            client.send(&ClientMessage::Next).unwrap();
+        }
+    }
+    for client in [&mut first, &mut second] {
+        assert_eq!(client.receive().unwrap(), ServerMessage::Draw { draw_index: 3, number: 4 });
+    }
+    assert_eq!(
+        first.request(&ClientMessage::Claim(0)).unwrap(),
+        ServerMessage::Error("Board 0 has not won on this draw!".to_string())
+    );
+    first.send(&ClientMessage::Next).unwrap();
+    second.send(&ClientMessage::Next).unwrap();
+    for client in [&mut first, &mut second] {
+        assert_eq!(client.receive().unwrap(), ServerMessage::End);
+    }
+
+    assert_eq!(server.join().unwrap().unwrap(), vec![]);
+}
+
+#[test]
+fn server_applies_board_options() {
+    let options = ServerOptions {
+        size: Some(BoardSize { rows: 2, columns: 2 }),
+        duplicates: Duplicates::Reject,
+        ..Default::default()
+    };
+    let (address, server) = serve(vec![1], options);
+    let mut client = Client::connect(address).unwrap();
+
+    let error = client.register(&[vec![1, 2, 3]]).unwrap_err();
+    assert_eq!(error.to_string(), "board 0, line 1: expected a 2x2 board but found 1x3");
+    let error = client.register(&[vec![1, 2], vec![3, 1]]).unwrap_err();
+    assert_eq!(error.to_string(), "board 0, line 2: 1 appears more than once");
+    assert_eq!(client.register(&[vec![1, 2], vec![3, 4]]).unwrap(), 0);
+    client.send(&ClientMessage::Ready).unwrap();
+    assert_eq!(client.receive().unwrap(), ServerMessage::Draw { draw_index: 0, number: 1 });
+    client.send(&ClientMessage::Next).unwrap();
+    assert_eq!(client.receive().unwrap(), ServerMessage::End);
+
+    assert_eq!(server.join().unwrap().unwrap(), vec![]);
+}
+
+#[test]
+fn server_gives_up_on_silent_player() {
+    let options = ServerOptions {
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let (address, server) = serve(vec![1], options);
+    let _client = Client::connect(address).unwrap();
+
+    assert!(server.join().unwrap().is_err());
+}
+
+#[test]
+fn server_gives_up_on_missing_player() {
+    let options = ServerOptions {
+        players: 2,
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let (address, server) = serve(vec![1], options);
+    let mut client = Client::connect(address).unwrap();
+    client.send(&ClientMessage::Ready).unwrap();
+
+    let error = server.join().unwrap().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+}
@@ -0,0 +1,271 @@
+//! Reading puzzle inputs, shared by every day.
+//!
+//! Lines are handed around as `(line number, text)` pairs with line numbers counted from
+//! 1, so parse errors can always point at the line they came from.
+
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// A token that isn't a valid value of the type it was parsed as.
+#[derive(Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub token: String,
+    /// The error the type's `FromStr` gave for the token.
+    pub source: Box<dyn Error + Send + Sync>,
+}
+
+/// Errors are equal when they point at the same token and their sources read the same.
+impl PartialEq for ParseError {
+    fn eq(&self, other: &Self) -> bool {
+        self.line == other.line
+            && self.token == other.token
+            && self.source.to_string() == other.source.to_string()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: cannot parse {:?}: {}",
+            self.line, self.token, self.source
+        )
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn parse_token<T: FromStr>(line: usize, token: &str) -> Result<T, ParseError>
+where
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    token.parse().map_err(|error: T::Err| ParseError {
+        line,
+        token: token.to_string(),
+        source: error.into(),
+    })
+}
+
+/// Every line of `reader` with its number. A `\r` left over from a `\r\n` line ending is
+/// dropped.
+pub fn lines<R: BufRead>(reader: R) -> impl Iterator<Item = (usize, String)> {
+    reader.lines().enumerate().map(|(i, line)| {
+        let line = line.expect("Cannot read line!");
+        (i + 1, line.trim_end_matches('\r').to_string())
+    })
+}
+
+/// Parses every line that isn't blank as one `T`, ignoring surrounding whitespace.
+pub fn parse_lines<T: FromStr, R: BufRead>(reader: R) -> impl Iterator<Item = Result<T, ParseError>>
+where
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    lines(reader)
+        .filter(|(_, line)| !is_blank(line))
+        .map(|(line_number, line)| parse_token(line_number, line.trim()))
+}
+
+/// Parses the whitespace separated tokens of a line.
+pub fn parse_tokens<T: FromStr>(line: usize, text: &str) -> Result<Vec<T>, ParseError>
+where
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    text.split_whitespace()
+        .map(|token| parse_token(line, token))
+        .collect()
+}
+
+/// Parses the tokens of a line split on `separator`, such as a comma separated list.
+/// Whitespace around tokens and empty tokens are ignored.
+pub fn parse_separated<T: FromStr>(line: usize, text: &str, separator: char) -> Result<Vec<T>, ParseError>
+where
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    text.split(separator)
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| parse_token(line, token))
+        .collect()
+}
+
+/// Groups lines into blocks separated by blank lines, see `blocks`.
+pub struct Blocks<I> {
+    lines: I,
+}
+
+impl<I: Iterator<Item = (usize, String)>> Iterator for Blocks<I> {
+    type Item = Vec<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = vec![];
+        for (line_number, line) in self.lines.by_ref() {
+            if !is_blank(&line) {
+                block.push((line_number, line));
+            } else if !block.is_empty() {
+                return Some(block);
+            }
+        }
+        if block.is_empty() {
+            None
+        } else {
+            Some(block)
+        }
+    }
+}
+
+/// Splits lines into blocks at blank lines. Lines holding only whitespace count as
+/// blank, and runs of blank lines or blank lines at either end never give empty blocks.
+pub fn blocks<I: Iterator<Item = (usize, String)>>(lines: I) -> Blocks<I> {
+    Blocks { lines }
+}
+
+/// Takes the first line that isn't blank as a header and leaves the lines after it as
+/// the body. Returns `None` when every line is blank.
+pub fn split_header<I: Iterator<Item = (usize, String)>>(mut lines: I) -> Option<((usize, String), I)> {
+    let header = lines.find(|(_, line)| !is_blank(line))?;
+    Some((header, lines))
+}
+
+/// Parses a block of lines as rows of whitespace separated values, each row keeping its
+/// line number. Rows may differ in length, checking the shape is up to the caller.
+pub fn parse_grid<T: FromStr>(block: &[(usize, String)]) -> Result<Vec<(usize, Vec<T>)>, ParseError>
+where
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    block
+        .iter()
+        .map(|(line_number, line)| Ok((*line_number, parse_tokens(*line_number, line)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn invalid_digit(line: usize, token: &str) -> ParseError {
+        ParseError {
+            line,
+            token: token.to_string(),
+            source: "invalid digit found in string".into(),
+        }
+    }
+
+    fn numbered(lines: &[&str]) -> Vec<(usize, String)> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn lines_numbers_lines_and_drops_carriage_returns() {
+        let actual: Vec<_> = lines("a\r\n\nb\r".as_bytes()).collect();
+        assert_eq!(actual, numbered(&["a", "", "b"]));
+    }
+
+    #[test]
+    fn parse_lines_skips_blank_lines() {
+        let actual: Result<Vec<i32>, _> = parse_lines("199\n 200 \n\n-3\n".as_bytes()).collect();
+        assert_eq!(actual, Ok(vec![199, 200, -3]));
+    }
+
+    #[test]
+    fn parse_lines_returns_line_of_invalid_value() {
+        let actual: Result<Vec<i32>, _> = parse_lines("1\n\nx\n".as_bytes()).collect();
+        assert_eq!(actual, Err(invalid_digit(3, "x")));
+        let error = actual.unwrap_err();
+        assert_eq!(error.to_string(), "line 3: cannot parse \"x\": invalid digit found in string");
+        assert_eq!(error.source().unwrap().to_string(), "invalid digit found in string");
+    }
+
+    #[test]
+    fn parse_lines_keeps_error_of_from_str() {
+        #[derive(Debug)]
+        struct Even;
+
+        impl FromStr for Even {
+            type Err = String;
+
+            fn from_str(token: &str) -> Result<Self, Self::Err> {
+                Err(format!("{} is odd!", token))
+            }
+        }
+
+        let actual: Result<Vec<Even>, _> = parse_lines("3\n".as_bytes()).collect();
+        assert_eq!(actual.unwrap_err().to_string(), "line 1: cannot parse \"3\": 3 is odd!");
+    }
+
+    #[test_case("10 11 12 16 18 17" => vec![10, 11, 12, 16, 18, 17])]
+    #[test_case(" 9  7 17  6" => vec![9, 7, 17, 6])]
+    #[test_case(" 2 11  8\n\n" => vec![2, 11, 8])]
+    #[test_case("\t4\t15 16\r" => vec![4, 15, 16])]
+    fn parse_tokens_splits_on_whitespace(text: &str) -> Vec<u32> {
+        parse_tokens(1, text).unwrap()
+    }
+
+    #[test_case("7,4,9" => Ok(vec![7, 4, 9]))]
+    #[test_case(" 7, 4 ,9,\r" => Ok(vec![7, 4, 9]))]
+    #[test_case("7,,4" => Ok(vec![7, 4]))]
+    #[test_case("7,four" => Err(invalid_digit(2, "four")))]
+    fn parse_separated_splits_on_separator(text: &str) -> Result<Vec<u32>, ParseError> {
+        parse_separated(2, text, ',')
+    }
+
+    #[test]
+    fn blocks_splits_on_blank_lines() {
+        let lines = numbered(&["", "a", "b", " \t", "", "c", ""]);
+
+        let actual: Vec<_> = blocks(lines.into_iter()).collect();
+        let expected = vec![
+            vec![(2, "a".to_string()), (3, "b".to_string())],
+            vec![(6, "c".to_string())],
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn split_header_takes_first_non_blank_line() {
+        let lines = numbered(&["", "1,2", "", "3 4"]);
+
+        let (header, body) = split_header(lines.into_iter()).unwrap();
+        assert_eq!(header, (2, "1,2".to_string()));
+        assert_eq!(body.collect::<Vec<_>>(), vec![(3, String::new()), (4, "3 4".to_string())]);
+    }
+
+    #[test]
+    fn split_header_returns_none_for_blank_input() {
+        assert!(split_header(numbered(&["", " "]).into_iter()).is_none());
+    }
+
+    #[test]
+    fn parse_grid_keeps_line_numbers() {
+        let block = vec![(4, "1 2".to_string()), (5, " 3  4 5".to_string())];
+
+        let actual = parse_grid::<u32>(&block);
+        assert_eq!(actual, Ok(vec![(4, vec![1, 2]), (5, vec![3, 4, 5])]));
+    }
+
+    #[test]
+    fn parse_grid_returns_line_of_invalid_value() {
+        let block = vec![(4, "1 2".to_string()), (5, "3 x".to_string())];
+
+        let actual = parse_grid::<u32>(&block);
+        assert_eq!(actual, Err(invalid_digit(5, "x")));
+    }
+}
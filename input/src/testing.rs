@@ -0,0 +1,10 @@
+//! Helpers shared by the benches and tests of every day, behind the `testing` feature.
+
+/// Advances a xorshift generator and returns its next value. Seeding it the same way
+/// gives the same pseudo-random input on every run.
+pub fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
@@ -0,0 +1,283 @@
+//! Checks every day's answers for its bundled input against the known-correct ones kept
+//! in its `answers.toml`, which maps each part to its answer:
+//!
+//! ```toml
+//! part_one = 1298
+//! part_two = 1248
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::panic;
+use std::time::{Duration, Instant};
+
+/// One way of solving a part of the puzzle.
+pub struct Part {
+    /// Key of the expected answer in `answers.toml`.
+    pub name: &'static str,
+    /// Tells apart several ways of solving the same part.
+    pub variant: Option<&'static str>,
+    pub solve: fn() -> String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String },
+    /// `answers.toml` has no answer for the part.
+    Unknown,
+    /// Solving the part panicked, which fails it like a wrong answer.
+    Panicked { message: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartReport {
+    pub name: String,
+    pub answer: String,
+    pub outcome: Outcome,
+    pub time: Duration,
+}
+
+impl fmt::Display for PartReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let time = format!("{:.2?}", self.time);
+        match &self.outcome {
+            Outcome::Pass => write!(f, "pass  {:<20} {:>12} {:>10}", self.name, self.answer, time),
+            Outcome::Fail { expected } => write!(
+                f,
+                "FAIL  {:<20} {:>12} {:>10}  expected {}",
+                self.name, self.answer, time, expected
+            ),
+            Outcome::Unknown => write!(
+                f,
+                "????  {:<20} {:>12} {:>10}  no stored answer",
+                self.name, self.answer, time
+            ),
+            Outcome::Panicked { message } => write!(
+                f,
+                "FAIL  {:<20} {:>12} {:>10}  panicked: {}",
+                self.name, self.answer, time, message
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub parts: Vec<PartReport>,
+}
+
+impl Report {
+    /// Whether every part has a stored answer and matches it.
+    pub fn passed(&self) -> bool {
+        self.parts.iter().all(|part| part.outcome == Outcome::Pass)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for part in &self.parts {
+            writeln!(f, "{}", part)?;
+        }
+        let passed = self
+            .parts
+            .iter()
+            .filter(|part| part.outcome == Outcome::Pass)
+            .count();
+        writeln!(f, "{} of {} parts passed", passed, self.parts.len())
+    }
+}
+
+/// Reads the answers by part. Answers may be integers or strings.
+pub fn parse_answers(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let table: toml::Table = text
+        .parse()
+        .map_err(|error| format!("Cannot parse answers: {}", error))?;
+    table
+        .into_iter()
+        .map(|(name, value)| match value {
+            toml::Value::Integer(answer) => Ok((name, answer.to_string())),
+            toml::Value::String(answer) => Ok((name, answer)),
+            _ => Err(format!("Unsupported answer for {}!", name)),
+        })
+        .collect()
+}
+
+pub fn read_answers(path: &str) -> Result<BTreeMap<String, String>, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path, error))?;
+    parse_answers(&text)
+}
+
+/// The message a part panicked with, if it was a string.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_else(|| "unknown panic".to_string()),
+    }
+}
+
+/// Solves every part in order, timing each one and comparing its answer with the stored one.
+/// A part that panics fails without stopping the parts after it.
+pub fn verify(answers: &BTreeMap<String, String>, parts: &[Part]) -> Report {
+    let parts = parts
+        .iter()
+        .map(|part| {
+            let start = Instant::now();
+            let solved = panic::catch_unwind(part.solve);
+            let time = start.elapsed();
+            let (answer, outcome) = match solved {
+                Ok(answer) => {
+                    let outcome = match answers.get(part.name) {
+                        Some(expected) if *expected == answer => Outcome::Pass,
+                        Some(expected) => Outcome::Fail {
+                            expected: expected.clone(),
+                        },
+                        None => Outcome::Unknown,
+                    };
+                    (answer, outcome)
+                }
+                Err(payload) => (
+                    "-".to_string(),
+                    Outcome::Panicked {
+                        message: panic_message(payload.as_ref()),
+                    },
+                ),
+            };
+            let name = match part.variant {
+                Some(variant) => format!("{} ({})", part.name, variant),
+                None => part.name.to_string(),
+            };
+            PartReport {
+                name,
+                answer,
+                outcome,
+                time,
+            }
+        })
+        .collect();
+
+    Report { parts }
+}
+
+/// Verifies the parts against the answers stored at `path` and prints the report.
+/// Returns whether every part passed.
+pub fn run(path: &str, parts: &[Part]) -> bool {
+    let answers = read_answers(path).expect("Cannot read answers!");
+    let report = verify(&answers, parts);
+    print!("{}", report);
+    report.passed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn answers() -> BTreeMap<String, String> {
+        parse_answers("part_one = 1298\npart_two = \"1248\"\n").unwrap()
+    }
+
+    #[test]
+    fn parse_answers_accepts_integers_and_strings() {
+        let expected = BTreeMap::from([
+            ("part_one".to_string(), "1298".to_string()),
+            ("part_two".to_string(), "1248".to_string()),
+        ]);
+        assert_eq!(answers(), expected);
+    }
+
+    #[test_case("part_one = 1.5" => Err("Unsupported answer for part_one!".to_string()))]
+    #[test_case("part_one = [1, 2]" => Err("Unsupported answer for part_one!".to_string()))]
+    fn parse_answers_rejects_other_values(text: &str) -> Result<BTreeMap<String, String>, String> {
+        parse_answers(text)
+    }
+
+    #[test]
+    fn parse_answers_rejects_invalid_toml() {
+        let error = parse_answers("part_one = ").unwrap_err();
+        assert!(error.starts_with("Cannot parse answers: "));
+    }
+
+    #[test]
+    fn verify_compares_every_part() {
+        let parts = [
+            Part {
+                name: "part_one",
+                variant: None,
+                solve: || 1298.to_string(),
+            },
+            Part {
+                name: "part_two",
+                variant: Some("slow"),
+                solve: || 1249.to_string(),
+            },
+            Part {
+                name: "part_three",
+                variant: None,
+                solve: || "?".to_string(),
+            },
+            Part {
+                name: "part_one",
+                variant: Some("broken"),
+                solve: || panic!("Cannot open file!"),
+            },
+        ];
+
+        let report = verify(&answers(), &parts);
+        let outcomes: Vec<(&str, &Outcome)> = report
+            .parts
+            .iter()
+            .map(|part| (part.name.as_str(), &part.outcome))
+            .collect();
+        let failed = Outcome::Fail {
+            expected: "1248".to_string(),
+        };
+        let panicked = Outcome::Panicked {
+            message: "Cannot open file!".to_string(),
+        };
+        assert_eq!(
+            outcomes,
+            vec![
+                ("part_one", &Outcome::Pass),
+                ("part_two (slow)", &failed),
+                ("part_three", &Outcome::Unknown),
+                ("part_one (broken)", &panicked),
+            ]
+        );
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn report_display_lists_parts_and_summary() {
+        let part = |answer: &str, outcome| PartReport {
+            name: "part_two (trie)".to_string(),
+            answer: answer.to_string(),
+            outcome,
+            time: Duration::from_micros(1500),
+        };
+        let report = Report {
+            parts: vec![
+                part("1248", Outcome::Pass),
+                part("1249", Outcome::Fail {
+                    expected: "1248".to_string(),
+                }),
+                part("-", Outcome::Panicked {
+                    message: "Cannot open file!".to_string(),
+                }),
+            ],
+        };
+
+        let expected = "\
+pass  part_two (trie)              1248     1.50ms
+FAIL  part_two (trie)              1249     1.50ms  expected 1248
+FAIL  part_two (trie)                 -     1.50ms  panicked: Cannot open file!
+1 of 3 parts passed
+";
+        assert_eq!(report.to_string(), expected);
+        assert!(!report.passed());
+    }
+}